@@ -1,12 +1,13 @@
-use std::fmt::Debug;
-use crate::httpc::HttpClient;
+use crate::error::{Error, Result};
+use crate::httpc::{HttpClient, HttpRequest};
 use crate::records::RecordId;
-use anyhow::{anyhow, Result};
-use reqwest::header::AUTHORIZATION;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use reqwest::RequestBuilder;
+use reqwest::header::AUTHORIZATION;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::fmt::Debug;
 use std::marker::PhantomData;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -16,6 +17,100 @@ pub struct AuthSuccessResponse {
     pub token: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct OtpResponse {
+    #[serde(rename = "otpId")]
+    otp_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OAuth2AuthResponse {
+    #[serde(flatten)]
+    auth: AuthSuccessResponse,
+    #[serde(default)]
+    meta: OAuth2Meta,
+}
+
+/// Provider details returned next to the auth record after an OAuth2 sign in.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OAuth2Meta {
+    pub id: String,
+    pub name: String,
+    pub username: String,
+    pub email: String,
+    pub is_new: bool,
+    #[serde(rename = "avatarURL")]
+    pub avatar_url: String,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expiry: String,
+    pub raw_user: serde_json::Value,
+}
+
+/// Sign in options enabled for an auth collection.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuthMethodsList {
+    pub password: PasswordAuthConfig,
+    pub oauth2: OAuth2AuthConfig,
+    pub mfa: MfaAuthConfig,
+    pub otp: OtpAuthConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PasswordAuthConfig {
+    pub enabled: bool,
+    pub identity_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OAuth2AuthConfig {
+    pub enabled: bool,
+    pub providers: Vec<OAuth2Provider>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MfaAuthConfig {
+    pub enabled: bool,
+    pub duration: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OtpAuthConfig {
+    pub enabled: bool,
+    pub duration: u64,
+}
+
+/// An OAuth2 provider with a freshly generated `state` and PKCE pair.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OAuth2Provider {
+    pub name: String,
+    pub display_name: String,
+    pub state: String,
+    #[serde(rename = "authURL")]
+    pub auth_url: String,
+    pub code_verifier: String,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+}
+
+impl OAuth2Provider {
+    /// The provider consent URL, which PocketBase returns without a redirect.
+    pub fn auth_url_with_redirect(&self, redirect_url: &str) -> String {
+        format!(
+            "{}{}",
+            self.auth_url,
+            utf8_percent_encode(redirect_url, NON_ALPHANUMERIC)
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthBaseFields {
     pub id: RecordId,
@@ -73,15 +168,17 @@ pub struct Auth<State: AuthState> {
     auth_store: State::Storage,
     record_value: serde_json::Value,
     http_client: HttpClient,
+    mfa_id: Option<String>,
 }
 
 impl Auth<Authorized> {
     pub fn new(auth_store: AuthStore, http_client: HttpClient) -> Result<Self> {
         Ok(Self {
             _state: PhantomData,
-            record_value: serde_json::to_value(&auth_store.record)?,
+            record_value: serde_json::to_value(&auth_store.record).map_err(Error::Encode)?,
             auth_store,
             http_client,
+            mfa_id: None,
         })
     }
 
@@ -92,7 +189,7 @@ impl Auth<Authorized> {
     pub fn record<T: Serialize + DeserializeOwned>(&self) -> Result<(AuthBaseFields, T)> {
         Ok((
             self.auth_store.record.base_fields.clone(),
-            serde_json::from_value(self.record_value.clone())?,
+            serde_json::from_value(self.record_value.clone()).map_err(Error::Decode)?,
         ))
     }
 
@@ -102,24 +199,34 @@ impl Auth<Authorized> {
             self.auth_store.record.base_fields.collection_name
         );
 
-        match self
+        let response = self
             .http_client
             .post(&url, "".to_string())
+            .route(
+                "/api/collections/{collection}/auth-refresh",
+                Some(&self.auth_store.record.base_fields.collection_name),
+            )
             .try_attach_auth_info(Some(self.auth_store.token.as_str()))
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let response = response.json::<AuthSuccessResponse>().await?;
+            .fetch::<AuthSuccessResponse>()
+            .await?;
 
-                self.auth_store.record = serde_json::from_value(response.record_value.clone())?;
-                self.record_value = response.record_value.clone();
-                self.auth_store.token = response.token.clone();
+        self.auth_store.record =
+            serde_json::from_value(response.record_value.clone()).map_err(Error::Decode)?;
+        self.record_value = response.record_value;
+        self.auth_store.token = response.token;
 
-                Ok(())
-            }
-            Err(e) => Err(anyhow!("{}", e)),
-        }
+        Ok(())
+    }
+
+    /// Emails a confirmation link to `new_email`, the address only changes
+    /// once it is confirmed with [`Auth::confirm_email_change`].
+    pub async fn request_email_change(&self, new_email: &str) -> Result<()> {
+        self.account_action(
+            "/api/collections/{collection}/request-email-change",
+            &self.auth_store.record.base_fields.collection_name,
+            json!({ "newEmail": new_email }),
+        )
+        .await
     }
 }
 
@@ -130,6 +237,7 @@ impl Auth<Unauthorized> {
             auth_store: (),
             record_value: serde_json::Value::Null,
             http_client: http_client.clone(),
+            mfa_id: None,
         }
     }
 }
@@ -139,6 +247,15 @@ impl<State: AuthState> Auth<State> {
         State::token(&self.auth_store)
     }
 
+    /// Sends the next sign in as the second factor of `mfa_id`, taken from
+    /// [`Error::MfaRequired`].
+    pub fn with_mfa(&self, mfa_id: &str) -> Self {
+        Self {
+            mfa_id: Some(mfa_id.to_string()),
+            ..self.clone()
+        }
+    }
+
     pub async fn auth_with_password(
         &self,
         collection: &str,
@@ -147,30 +264,237 @@ impl<State: AuthState> Auth<State> {
     ) -> Result<Auth<Authorized>> {
         let url = format!("/api/collections/{}/auth-with-password", collection);
 
-        let auth_payload = json!({
+        let auth_payload = self.mfa_payload(json!({
             "identity": identifier,
             "password": secret
+        }));
+
+        let response = self
+            .http_client
+            .post(&url, auth_payload.to_string())
+            .route(
+                "/api/collections/{collection}/auth-with-password",
+                Some(collection),
+            )
+            .fetch::<AuthSuccessResponse>()
+            .await
+            .map_err(mfa_error)?;
+
+        self.authorize(response)
+    }
+
+    pub async fn list_auth_methods(&self, collection: &str) -> Result<AuthMethodsList> {
+        let url = format!("/api/collections/{}/auth-methods", collection);
+
+        self.http_client
+            .get(&url, None)
+            .route(
+                "/api/collections/{collection}/auth-methods",
+                Some(collection),
+            )
+            .try_attach_auth_info(self.token())
+            .fetch::<AuthMethodsList>()
+            .await
+    }
+
+    /// Exchanges an authorization code from the provider redirect.
+    /// `create_data` fills the record when the sign in creates a new user.
+    pub async fn auth_with_oauth2_code(
+        &self,
+        collection: &str,
+        provider: &str,
+        code: &str,
+        code_verifier: &str,
+        redirect_url: &str,
+        create_data: Option<serde_json::Value>,
+    ) -> Result<(Auth<Authorized>, OAuth2Meta)> {
+        let url = format!("/api/collections/{}/auth-with-oauth2", collection);
+
+        let mut auth_payload = json!({
+            "provider": provider,
+            "code": code,
+            "codeVerifier": code_verifier,
+            "redirectURL": redirect_url
         });
+        if let Some(create_data) = create_data {
+            auth_payload["createData"] = create_data;
+        }
 
-        match self
+        let response = self
+            .http_client
+            .post(&url, self.mfa_payload(auth_payload).to_string())
+            .route(
+                "/api/collections/{collection}/auth-with-oauth2",
+                Some(collection),
+            )
+            .fetch::<OAuth2AuthResponse>()
+            .await
+            .map_err(mfa_error)?;
+
+        Ok((self.authorize(response.auth)?, response.meta))
+    }
+
+    /// Emails a one-time password and returns the id to sign in with. To
+    /// avoid leaking which emails exist, unknown ones get an id too.
+    pub async fn request_otp(&self, collection: &str, email: &str) -> Result<String> {
+        let url = format!("/api/collections/{}/request-otp", collection);
+
+        let response = self
+            .http_client
+            .post(&url, json!({ "email": email }).to_string())
+            .route(
+                "/api/collections/{collection}/request-otp",
+                Some(collection),
+            )
+            .fetch::<OtpResponse>()
+            .await?;
+
+        Ok(response.otp_id)
+    }
+
+    /// Fails with [`Error::InvalidOtp`] when the code is wrong or expired.
+    pub async fn auth_with_otp(
+        &self,
+        collection: &str,
+        otp_id: &str,
+        code: &str,
+    ) -> Result<Auth<Authorized>> {
+        let url = format!("/api/collections/{}/auth-with-otp", collection);
+
+        let auth_payload = self.mfa_payload(json!({
+            "otpId": otp_id,
+            "password": code
+        }));
+
+        let response = self
             .http_client
             .post(&url, auth_payload.to_string())
-            .send()
+            .route(
+                "/api/collections/{collection}/auth-with-otp",
+                Some(collection),
+            )
+            .fetch::<AuthSuccessResponse>()
             .await
-        {
-            Ok(response) => {
-                let response = response.json::<AuthSuccessResponse>().await?;
-
-                Ok(Auth::<Authorized>::new(
-                    AuthStore {
-                        record: serde_json::from_value::<AuthRecord>(response.record_value)?,
-                        token: response.token,
-                    },
-                    self.http_client.to_owned(),
-                )?)
-            }
-            Err(e) => Err(anyhow!("{}", e)),
+            .map_err(|e| match mfa_error(e) {
+                Error::Api(e) if e.status == 400 && e.validation_errors().is_empty() => {
+                    Error::InvalidOtp(e)
+                }
+                e => e,
+            })?;
+
+        self.authorize(response)
+    }
+
+    /// Succeeds for unknown emails too, so it can not be used to probe
+    /// which accounts exist.
+    pub async fn request_password_reset(&self, collection: &str, email: &str) -> Result<()> {
+        self.account_action(
+            "/api/collections/{collection}/request-password-reset",
+            collection,
+            json!({ "email": email }),
+        )
+        .await
+    }
+
+    pub async fn confirm_password_reset(
+        &self,
+        collection: &str,
+        token: &str,
+        password: &str,
+        password_confirm: &str,
+    ) -> Result<()> {
+        self.account_action(
+            "/api/collections/{collection}/confirm-password-reset",
+            collection,
+            json!({
+                "token": token,
+                "password": password,
+                "passwordConfirm": password_confirm
+            }),
+        )
+        .await
+    }
+
+    pub async fn request_verification(&self, collection: &str, email: &str) -> Result<()> {
+        self.account_action(
+            "/api/collections/{collection}/request-verification",
+            collection,
+            json!({ "email": email }),
+        )
+        .await
+    }
+
+    pub async fn confirm_verification(&self, collection: &str, token: &str) -> Result<()> {
+        self.account_action(
+            "/api/collections/{collection}/confirm-verification",
+            collection,
+            json!({ "token": token }),
+        )
+        .await
+    }
+
+    /// `password` is the current password of the account.
+    pub async fn confirm_email_change(
+        &self,
+        collection: &str,
+        token: &str,
+        password: &str,
+    ) -> Result<()> {
+        self.account_action(
+            "/api/collections/{collection}/confirm-email-change",
+            collection,
+            json!({ "token": token, "password": password }),
+        )
+        .await
+    }
+
+    async fn account_action(
+        &self,
+        route: &'static str,
+        collection: &str,
+        payload: serde_json::Value,
+    ) -> Result<()> {
+        let url = route.replace("{collection}", collection);
+
+        self.http_client
+            .post(&url, payload.to_string())
+            .route(route, Some(collection))
+            .try_attach_auth_info(self.token())
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    fn mfa_payload(&self, mut payload: serde_json::Value) -> serde_json::Value {
+        if let Some(mfa_id) = &self.mfa_id {
+            payload["mfaId"] = mfa_id.clone().into();
         }
+        payload
+    }
+
+    fn authorize(&self, response: AuthSuccessResponse) -> Result<Auth<Authorized>> {
+        Auth::<Authorized>::new(
+            AuthStore {
+                record: serde_json::from_value::<AuthRecord>(response.record_value)
+                    .map_err(Error::Decode)?,
+                token: response.token,
+            },
+            self.http_client.to_owned(),
+        )
+    }
+}
+
+/// Turns the 401 answering a first factor into [`Error::MfaRequired`].
+fn mfa_error(e: Error) -> Error {
+    match e {
+        Error::Api(e) if e.status == 401 => match e.data.get("mfaId").and_then(|id| id.as_str()) {
+            Some(mfa_id) => Error::MfaRequired {
+                mfa_id: mfa_id.to_string(),
+            },
+            None => Error::Api(e),
+        },
+        e => e,
     }
 }
 
@@ -191,3 +515,13 @@ impl AuthenticatedRequest for RequestBuilder {
         self
     }
 }
+
+impl AuthenticatedRequest for HttpRequest {
+    fn attach_auth_info(self, token: &str) -> Self {
+        self.map(|builder| builder.attach_auth_info(token))
+    }
+
+    fn try_attach_auth_info(self, token: Option<&str>) -> Self {
+        self.map(|builder| builder.try_attach_auth_info(token))
+    }
+}
@@ -0,0 +1,169 @@
+use crate::auth::AuthenticatedRequest;
+use crate::error::{ApiError, Error, Result};
+use crate::httpc::{HttpClient, RequestKey};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize)]
+struct BatchOperation {
+    method: &'static str,
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+/// Record writes sent to `/api/batch` and executed in a single transaction.
+/// Either every operation is applied or none is.
+#[derive(Debug)]
+pub struct BatchRequestBuilder<'a> {
+    pub(crate) client: &'a HttpClient,
+    pub(crate) token: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
+    requests: Vec<BatchOperation>,
+    error: Option<Error>,
+}
+
+/// Queues operations on one collection of a batch.
+#[derive(Debug)]
+pub struct BatchCollection<'b, 'a> {
+    batch: &'b mut BatchRequestBuilder<'a>,
+    collection_name: String,
+}
+
+/// Outcome of a single batch operation, in the order it was queued.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchResult {
+    pub status: u16,
+    #[serde(default)]
+    pub body: Value,
+}
+
+impl BatchResult {
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(self.body.clone()).map_err(Error::Decode)
+    }
+}
+
+impl<'a> BatchRequestBuilder<'a> {
+    pub(crate) fn new(client: &'a HttpClient, token: Option<&'a str>) -> Self {
+        Self {
+            client,
+            token,
+            request_key: RequestKey::Auto,
+            requests: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn collection(&mut self, collection_name: &str) -> BatchCollection<'_, 'a> {
+        BatchCollection {
+            batch: self,
+            collection_name: collection_name.to_string(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    pub fn request_key(mut self, key: Option<&str>) -> Self {
+        self.request_key = RequestKey::from_option(key);
+        self
+    }
+
+    fn push<T: Serialize>(&mut self, method: &'static str, url: String, body: Option<&T>) {
+        let body = match body.map(serde_json::to_value).transpose() {
+            Ok(body) => body,
+            Err(e) => {
+                self.error.get_or_insert(Error::Encode(e));
+                None
+            }
+        };
+
+        self.requests.push(BatchOperation { method, url, body });
+    }
+
+    /// Sends all queued operations. A failing operation rolls back the whole
+    /// batch and is reported as [`Error::Api`], see
+    /// [`ApiError::batch_failures`].
+    pub async fn send(self) -> Result<Vec<BatchResult>> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let payload = serde_json::to_string(&serde_json::json!({ "requests": self.requests }))
+            .map_err(Error::Encode)?;
+
+        self.client
+            .post("/api/batch", payload)
+            .route("/api/batch", None)
+            .request_key(&self.request_key)
+            .try_attach_auth_info(self.token)
+            .fetch::<Vec<BatchResult>>()
+            .await
+    }
+}
+
+impl BatchCollection<'_, '_> {
+    fn records_url(&self) -> String {
+        format!("/api/collections/{}/records", self.collection_name)
+    }
+
+    fn record_url(&self, id: &str) -> String {
+        format!("/api/collections/{}/records/{}", self.collection_name, id)
+    }
+
+    pub fn create<T: Serialize>(&mut self, record: &T) -> &mut Self {
+        let url = self.records_url();
+        self.batch.push("POST", url, Some(record));
+        self
+    }
+
+    pub fn update<T: Serialize>(&mut self, id: &str, data: &T) -> &mut Self {
+        let url = self.record_url(id);
+        self.batch.push("PATCH", url, Some(data));
+        self
+    }
+
+    /// Creates the record, or updates it if `record` carries the `id` of an
+    /// existing one.
+    pub fn upsert<T: Serialize>(&mut self, record: &T) -> &mut Self {
+        let url = self.records_url();
+        self.batch.push("PUT", url, Some(record));
+        self
+    }
+
+    pub fn delete(&mut self, id: &str) -> &mut Self {
+        let url = self.record_url(id);
+        self.batch.push::<Value>("DELETE", url, None);
+        self
+    }
+}
+
+impl ApiError {
+    /// Errors of the individual operations of a rejected batch, keyed by
+    /// their position in the batch.
+    pub fn batch_failures(&self) -> BTreeMap<usize, ApiError> {
+        let Some(Value::Object(requests)) = self.data.get("requests") else {
+            return BTreeMap::new();
+        };
+
+        requests
+            .iter()
+            .filter_map(|(index, failure)| {
+                let index = index.parse::<usize>().ok()?;
+                let error = match failure.get("response") {
+                    Some(response) => serde_json::from_value::<ApiError>(response.clone()).ok(),
+                    None => serde_json::from_value::<ApiError>(failure.clone()).ok(),
+                }?;
+                Some((index, error))
+            })
+            .collect()
+    }
+}
@@ -1,9 +1,21 @@
-use crate::auth::{Auth, AuthState, AuthStore, AuthenticatedRequest, Authorized, Unauthorized};
+use crate::auth::{
+    Auth, AuthMethodsList, AuthState, AuthStore, AuthenticatedRequest, Authorized, OAuth2Meta,
+    Unauthorized,
+};
+use crate::batch::BatchRequestBuilder;
+use crate::error::{Error, Result};
+use crate::files::FilesManager;
+use crate::middleware::{Middleware, Middlewares};
 use crate::realtime::RealtimeClient;
+use crate::retry::RetryPolicy;
+use crate::send::SendRequestBuilder;
 use crate::{collections::CollectionsManager, httpc::HttpClient};
 use crate::{logs::LogsManager, records::RecordsManager};
-use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use reqwest::{Certificate, Method, Proxy};
 use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct Client<State: AuthState> {
@@ -12,6 +24,170 @@ pub struct Client<State: AuthState> {
     auth: Auth<State>,
 }
 
+/// Configures the HTTP layer shared by every manager and the realtime client.
+///
+/// `proxy`, `add_root_certificate`, `connect_timeout` and `read_timeout` shape
+/// the underlying `reqwest::Client` and are ignored when one is supplied via
+/// [`ClientBuilder::http_client`]. `timeout`, `user_agent` and
+/// `default_header` are applied per request and work with either.
+#[derive(Debug)]
+pub struct ClientBuilder {
+    base_url: String,
+    http_client: Option<reqwest::Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    proxies: Vec<Proxy>,
+    root_certificates: Vec<Certificate>,
+    headers: HeaderMap,
+    retry_policy: RetryPolicy,
+    middlewares: Middlewares,
+    auto_cancellation: bool,
+    error: Option<Error>,
+}
+
+impl ClientBuilder {
+    fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            http_client: None,
+            timeout: None,
+            connect_timeout: None,
+            read_timeout: None,
+            proxies: Vec::new(),
+            root_certificates: Vec::new(),
+            headers: HeaderMap::new(),
+            retry_policy: RetryPolicy::none(),
+            middlewares: Middlewares::default(),
+            auto_cancellation: false,
+            error: None,
+        }
+    }
+
+    /// Use an existing `reqwest::Client` instead of building one.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Total time allowed for each request, from connecting until the body is
+    /// read. The realtime event stream is exempt.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum idle time between two reads. Unlike `timeout` this also
+    /// applies to the realtime event stream.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    pub fn user_agent(self, user_agent: &str) -> Self {
+        self.default_header(USER_AGENT.as_str(), user_agent)
+    }
+
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.headers.insert(name, value);
+            }
+            _ => {
+                self.error
+                    .get_or_insert(Error::Config(format!("invalid header `{}`", name)));
+            }
+        }
+        self
+    }
+
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Retry failed requests according to `retry_policy`. Requests are sent
+    /// only once by default.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Register a middleware. Middlewares run in the order they were added.
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Whether starting a request cancels a pending one with the same request
+    /// key, reads being keyed by `METHOD /path` unless given their own key.
+    /// Disabled by default, as unrelated callers sharing a client would
+    /// otherwise cancel each other's reads.
+    pub fn auto_cancellation(mut self, enabled: bool) -> Self {
+        self.auto_cancellation = enabled;
+        self
+    }
+
+    fn build_http_client(self) -> Result<HttpClient> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.read_timeout {
+                    builder = builder.read_timeout(timeout);
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+                for certificate in self.root_certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(HttpClient::with_client(&self.base_url, client)
+            .timeout(self.timeout)
+            .headers(self.headers)
+            .retry_policy(self.retry_policy)
+            .middlewares(self.middlewares)
+            .auto_cancellation(self.auto_cancellation))
+    }
+
+    pub fn build(self) -> Result<Client<Unauthorized>> {
+        Ok(Client::from_http_client(self.build_http_client()?))
+    }
+
+    pub fn build_with_auth(self, auth_store: AuthStore) -> Result<Client<Authorized>> {
+        Client::from_http_client_with_auth(self.build_http_client()?, auth_store)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct HealthCheckResponse {
     pub code: i32,
@@ -27,6 +203,20 @@ impl<State: AuthState> Client<State> {
         self.http_client.base_url()
     }
 
+    /// Cancels the pending requests with the given request key. Their futures
+    /// resolve to [`Error::Cancelled`].
+    ///
+    /// Requests are keyed when a builder sets a key with `request_key`. With
+    /// [`ClientBuilder::auto_cancellation`] enabled, reads are also keyed by
+    /// `METHOD /path` otherwise.
+    pub fn cancel(&self, request_key: &str) {
+        self.http_client.cancel(request_key);
+    }
+
+    pub fn cancel_all(&self) {
+        self.http_client.cancel_all();
+    }
+
     pub fn collection(&self, collection_name: &'static str) -> RecordsManager<'_> {
         RecordsManager {
             http_client: &self.http_client,
@@ -36,6 +226,32 @@ impl<State: AuthState> Client<State> {
         }
     }
 
+    /// Request against any route relative to the base url, e.g. a custom
+    /// `/api/myapp/...` route, carrying this client's auth token.
+    pub fn send(&self, method: Method, path: &str) -> SendRequestBuilder<'_> {
+        SendRequestBuilder::new(&self.http_client, self.auth.token(), method, path)
+    }
+
+    pub fn files(&self) -> FilesManager<'_> {
+        FilesManager {
+            client: &self.http_client,
+            token: self.auth.token(),
+        }
+    }
+
+    /// Collects record writes across collections into one transaction.
+    pub fn batch(&self) -> BatchRequestBuilder<'_> {
+        BatchRequestBuilder::new(&self.http_client, self.auth.token())
+    }
+
+    /// A client whose next sign in finishes the MFA flow of `mfa_id`.
+    pub fn with_mfa(&self, mfa_id: &str) -> Self {
+        Client {
+            auth: self.auth.with_mfa(mfa_id),
+            ..self.clone()
+        }
+    }
+
     pub async fn auth_with_password(
         &self,
         collection: &str,
@@ -53,11 +269,106 @@ impl<State: AuthState> Client<State> {
             realtime_client: self.realtime_client.clone(),
         })
     }
+
+    pub async fn list_auth_methods(&self, collection: &str) -> Result<AuthMethodsList> {
+        self.auth.list_auth_methods(collection).await
+    }
+
+    pub async fn request_otp(&self, collection: &str, email: &str) -> Result<String> {
+        self.auth.request_otp(collection, email).await
+    }
+
+    pub async fn auth_with_otp(
+        &self,
+        collection: &str,
+        otp_id: &str,
+        code: &str,
+    ) -> Result<Client<Authorized>> {
+        let auth = self.auth.auth_with_otp(collection, otp_id, code).await?;
+
+        Ok(Client {
+            auth,
+            http_client: self.http_client.clone(),
+            realtime_client: self.realtime_client.clone(),
+        })
+    }
+
+    pub async fn auth_with_oauth2_code(
+        &self,
+        collection: &str,
+        provider: &str,
+        code: &str,
+        code_verifier: &str,
+        redirect_url: &str,
+        create_data: Option<serde_json::Value>,
+    ) -> Result<(Client<Authorized>, OAuth2Meta)> {
+        let (auth, meta) = self
+            .auth
+            .auth_with_oauth2_code(
+                collection,
+                provider,
+                code,
+                code_verifier,
+                redirect_url,
+                create_data,
+            )
+            .await?;
+
+        Ok((
+            Client {
+                auth,
+                http_client: self.http_client.clone(),
+                realtime_client: self.realtime_client.clone(),
+            },
+            meta,
+        ))
+    }
+
+    pub async fn request_password_reset(&self, collection: &str, email: &str) -> Result<()> {
+        self.auth.request_password_reset(collection, email).await
+    }
+
+    pub async fn confirm_password_reset(
+        &self,
+        collection: &str,
+        token: &str,
+        password: &str,
+        password_confirm: &str,
+    ) -> Result<()> {
+        self.auth
+            .confirm_password_reset(collection, token, password, password_confirm)
+            .await
+    }
+
+    pub async fn request_verification(&self, collection: &str, email: &str) -> Result<()> {
+        self.auth.request_verification(collection, email).await
+    }
+
+    pub async fn confirm_verification(&self, collection: &str, token: &str) -> Result<()> {
+        self.auth.confirm_verification(collection, token).await
+    }
+
+    pub async fn confirm_email_change(
+        &self,
+        collection: &str,
+        token: &str,
+        password: &str,
+    ) -> Result<()> {
+        self.auth
+            .confirm_email_change(collection, token, password)
+            .await
+    }
 }
 impl Client<Unauthorized> {
     pub fn new(base_url: &str) -> Client<Unauthorized> {
-        let http_client = HttpClient::new(base_url);
+        Self::from_http_client(HttpClient::new(base_url))
+    }
 
+    pub fn builder(base_url: &str) -> ClientBuilder {
+        ClientBuilder::new(base_url)
+    }
+
+    fn from_http_client(http_client: HttpClient) -> Client<Unauthorized> {
         Client {
             auth: Auth::<Unauthorized>::new(http_client.clone()),
             realtime_client: RealtimeClient::new(http_client.clone()),
@@ -66,17 +377,23 @@ impl Client<Unauthorized> {
     }
 
     pub async fn health_check(&self) -> Result<HealthCheckResponse> {
-        match self.http_client.get("/api/health", None).send().await {
-            Ok(response) => Ok(response.json::<HealthCheckResponse>().await?),
-            Err(e) => Err(anyhow!("{}", e)),
-        }
+        self.http_client
+            .get("/api/health", None)
+            .route("/api/health", None)
+            .fetch::<HealthCheckResponse>()
+            .await
     }
 }
 
 impl Client<Authorized> {
     pub fn new_with_auth(base_url: &str, auth_store: AuthStore) -> Result<Client<Authorized>> {
-        let http_client = HttpClient::new(base_url);
+        Self::from_http_client_with_auth(HttpClient::new(base_url), auth_store)
+    }
 
+    fn from_http_client_with_auth(
+        http_client: HttpClient,
+        auth_store: AuthStore,
+    ) -> Result<Client<Authorized>> {
         Ok(Client {
             auth: Auth::<Authorized>::new(auth_store, http_client.clone())?,
             realtime_client: RealtimeClient::new(http_client.clone()),
@@ -108,16 +425,16 @@ impl Client<Authorized> {
         }
     }
 
+    pub async fn request_email_change(&self, new_email: &str) -> Result<()> {
+        self.auth.request_email_change(new_email).await
+    }
+
     pub async fn health_check(&self) -> Result<HealthCheckResponse> {
-        match self
-            .http_client
+        self.http_client
             .get("/api/health", None)
+            .route("/api/health", None)
             .attach_auth_info(self.auth_token())
-            .send()
+            .fetch::<HealthCheckResponse>()
             .await
-        {
-            Ok(response) => Ok(response.json::<HealthCheckResponse>().await?),
-            Err(e) => Err(anyhow!("{}", e)),
-        }
     }
 }
@@ -1,6 +1,7 @@
 use crate::auth::AuthenticatedRequest;
-use crate::httpc::HttpClient;
-use anyhow::Result;
+use crate::error::{Error, Result};
+use crate::filter::Filter;
+use crate::httpc::{HttpClient, RequestKey};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -53,8 +54,10 @@ pub struct CollectionsManager<'a> {
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CollectionDetails<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<&'a str>,
     pub name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<&'a str>,
     pub schema: Vec<FieldDeclaration<'a>>,
     pub system: bool,
@@ -78,6 +81,7 @@ pub struct CollectionCreateRequestBuilder<'a> {
 pub struct CollectionViewRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: &'a str,
+    pub(crate) request_key: RequestKey,
     pub name: &'a str,
 }
 
@@ -85,7 +89,8 @@ pub struct CollectionViewRequestBuilder<'a> {
 pub struct CollectionListRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: &'a str,
-    pub filter: Option<String>,
+    pub(crate) request_key: RequestKey,
+    pub filter: Option<Filter>,
     pub sort: Option<String>,
     pub per_page: i32,
     pub page: i32,
@@ -95,7 +100,8 @@ impl<'a> CollectionListRequestBuilder<'a> {
     pub async fn call(&self) -> Result<CollectionList> {
         let mut build_opts: Vec<(&str, &str)> = Vec::new();
 
-        if let Some(filter_opts) = &self.filter {
+        let filter_opts = self.filter.as_ref().map(Filter::validate).transpose()?;
+        if let Some(filter_opts) = &filter_opts {
             build_opts.push(("filter", filter_opts))
         }
         if let Some(sort_opts) = &self.sort {
@@ -106,21 +112,18 @@ impl<'a> CollectionListRequestBuilder<'a> {
         build_opts.push(("perPage", per_page_opts.as_str()));
         build_opts.push(("page", page_opts.as_str()));
 
-        match self
-            .client
+        self.client
             .get("/api/collections", Some(build_opts))
+            .route("/api/collections", None)
+            .request_key(&self.request_key)
             .attach_auth_info(self.token)
-            .send()
+            .fetch::<CollectionList>()
             .await
-        {
-            Ok(result) => Ok(result.json::<CollectionList>().await?),
-            Err(e) => Err(e.into()),
-        }
     }
 
-    pub fn filter(&self, filter_opts: String) -> Self {
+    pub fn filter(&self, filter_opts: impl Into<Filter>) -> Self {
         Self {
-            filter: Some(filter_opts),
+            filter: Some(filter_opts.into()),
             ..self.clone()
         }
     }
@@ -145,6 +148,13 @@ impl<'a> CollectionListRequestBuilder<'a> {
             ..self.clone()
         }
     }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
+        }
+    }
 }
 
 impl<'a> CollectionsManager<'a> {
@@ -152,6 +162,7 @@ impl<'a> CollectionsManager<'a> {
         CollectionViewRequestBuilder {
             client: self.client,
             token: self.token,
+            request_key: RequestKey::Auto,
             name,
         }
     }
@@ -169,6 +180,7 @@ impl<'a> CollectionsManager<'a> {
         CollectionListRequestBuilder {
             client: self.client,
             token: self.token,
+            request_key: RequestKey::Auto,
             filter: None,
             sort: None,
             per_page: 100,
@@ -177,18 +189,63 @@ impl<'a> CollectionsManager<'a> {
     }
 }
 
+impl<'a> CollectionCreateRequestBuilder<'a> {
+    /// Creates the collection, a `base` one without fields unless `details`
+    /// were given. The name passed to `create` takes precedence.
+    pub async fn call(&self) -> Result<Collection> {
+        let body = match &self.collection_details {
+            Some(details) => CollectionDetails {
+                name: Some(self.collection_name),
+                ..details.clone()
+            },
+            None => CollectionDetails {
+                id: None,
+                name: Some(self.collection_name),
+                r#type: Some("base"),
+                schema: Vec::new(),
+                system: false,
+                list_rule: None,
+                view_rule: None,
+                create_rule: None,
+                update_rule: None,
+                delete_rule: None,
+                indexes: Vec::new(),
+            },
+        };
+        let body = serde_json::to_string(&body).map_err(Error::Encode)?;
+
+        self.client
+            .post("/api/collections", body)
+            .route("/api/collections", None)
+            .attach_auth_info(self.token)
+            .fetch::<Collection>()
+            .await
+    }
+
+    pub fn details(&self, details: CollectionDetails<'a>) -> Self {
+        Self {
+            collection_details: Some(details),
+            ..self.clone()
+        }
+    }
+}
+
 impl<'a> CollectionViewRequestBuilder<'a> {
     pub async fn call(&self) -> Result<Collection> {
         let url = format!("/api/collections/{}", self.name);
-        match self
-            .client
+        self.client
             .get(&url, None)
+            .route("/api/collections/{collection}", Some(self.name))
+            .request_key(&self.request_key)
             .attach_auth_info(self.token)
-            .send()
+            .fetch::<Collection>()
             .await
-        {
-            Ok(result) => Ok(result.json::<Collection>().await?),
-            Err(e) => Err(e.into()),
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
         }
     }
 }
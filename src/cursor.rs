@@ -0,0 +1,171 @@
+use crate::error::{Error, Result};
+use crate::filter::{Filter, field};
+use crate::query::Sort;
+use crate::records::RecordsListRequestBuilder;
+use futures_util::Stream;
+use futures_util::stream;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Position after the last record of a keyset page. It serializes, so long
+/// scans can persist it and resume after a restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    keys: Vec<String>,
+    values: Vec<Value>,
+}
+
+/// Keyset pagination over a record list. Instead of page offsets, each page
+/// is requested with a filter on the sort keys of the last record seen, so
+/// deep pages stay fast and inserts during a scan do not shift rows.
+///
+/// The last key must be unique, `created,id` is used by default.
+#[derive(Debug, Clone)]
+pub struct KeysetRequestBuilder<'a> {
+    pub(crate) list: RecordsListRequestBuilder<'a>,
+    pub keys: Vec<String>,
+    pub cursor: Option<Cursor>,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeysetPage<T> {
+    pub items: Vec<T>,
+    /// Where the next page starts, `None` if no record was seen yet.
+    pub cursor: Option<Cursor>,
+    pub has_more: bool,
+}
+
+impl Cursor {
+    fn from_record(keys: &[String], record: &Value) -> Result<Self> {
+        let values = keys
+            .iter()
+            .map(|key| {
+                record.get(key).cloned().ok_or_else(|| {
+                    Error::Query(format!("keyset key `{}` is missing from the records", key))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            keys: keys.to_vec(),
+            values,
+        })
+    }
+
+    /// `k1 > v1 || k1 = v1 && k2 > v2 || ...`
+    fn filter(&self) -> Option<Filter> {
+        (0..self.keys.len())
+            .map(|position| {
+                let equal = (0..position)
+                    .map(|index| field(&self.keys[index]).eq(self.values[index].clone()));
+                let greater = field(&self.keys[position]).gt(self.values[position].clone());
+                equal.chain([greater]).reduce(Filter::and).unwrap()
+            })
+            .reduce(Filter::or)
+    }
+}
+
+impl<'a> KeysetRequestBuilder<'a> {
+    pub fn keys(&self, keys: &[&str]) -> Self {
+        Self {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Continues a scan after `cursor`.
+    pub fn after(&self, cursor: Cursor) -> Self {
+        Self {
+            cursor: Some(cursor),
+            ..self.clone()
+        }
+    }
+
+    fn request(&self) -> Result<RecordsListRequestBuilder<'a>> {
+        if self.list.sort.is_some() {
+            return Err(Error::Query(
+                "keyset pagination sorts by its keys and can not be combined with sort".to_string(),
+            ));
+        }
+        let sort = self
+            .keys
+            .iter()
+            .fold(Sort::new(), |sort, key| sort.asc(key));
+        sort.validate()?;
+        if let Some(cursor) = &self.cursor
+            && cursor.keys != self.keys
+        {
+            return Err(Error::Query(format!(
+                "cursor was created for the keys `{}`",
+                cursor.keys.join(",")
+            )));
+        }
+
+        let after = self.cursor.as_ref().and_then(Cursor::filter);
+        let filter = match (&self.list.filter, after) {
+            (Some(filter), Some(after)) => Some(filter.clone().group().and(after.group())),
+            (filter, None) => filter.clone(),
+            (None, Some(after)) => Some(after),
+        };
+
+        Ok(RecordsListRequestBuilder {
+            filter,
+            sort: Some(sort),
+            page: 1,
+            skip_total: true,
+            ..self.list.clone()
+        })
+    }
+
+    pub async fn call<T: DeserializeOwned>(&self) -> Result<KeysetPage<T>> {
+        let list = self.request()?.call::<Value>().await?;
+        let has_more = !list.is_last_page();
+        let cursor = match list.items.last() {
+            Some(last) => Some(Cursor::from_record(&self.keys, last)?),
+            None => self.cursor.clone(),
+        };
+        let items = list
+            .items
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<T>, _>>()
+            .map_err(Error::Decode)?;
+
+        Ok(KeysetPage {
+            items,
+            cursor,
+            has_more,
+        })
+    }
+
+    /// Pages of the scan, each requested once the previous one arrived.
+    pub fn into_page_stream<T: DeserializeOwned + Send>(
+        self,
+    ) -> impl Stream<Item = Result<KeysetPage<T>>> + Send + 'a {
+        stream::unfold(Some(self), |request| async move {
+            let request = request?;
+            match request.call::<T>().await {
+                Ok(page) => {
+                    let next = match (&page.cursor, page.has_more) {
+                        (Some(cursor), true) => Some(request.after(cursor.clone())),
+                        _ => None,
+                    };
+                    Some((Ok(page), next))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+}
+
+impl<'a> RecordsListRequestBuilder<'a> {
+    /// Switches to keyset pagination, see [`KeysetRequestBuilder`].
+    pub fn keyset(&self) -> KeysetRequestBuilder<'a> {
+        KeysetRequestBuilder {
+            list: self.clone(),
+            keys: vec!["created".to_string(), "id".to_string()],
+            cursor: None,
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The request could not be sent or the response could not be read
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// PocketBase answered with a non-2xx status
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    /// The response body did not match the expected type
+    #[error("failed to decode response: {0}")]
+    Decode(#[source] serde_json::Error),
+
+    /// The request payload could not be serialized
+    #[error("failed to encode request: {0}")]
+    Encode(#[source] serde_json::Error),
+
+    /// The client could not be configured
+    #[error("invalid client configuration: {0}")]
+    Config(String),
+
+    /// A filter, sort, expand or keyset option was rejected before the
+    /// request was sent
+    #[error("{0}")]
+    Query(String),
+
+    /// The request was cancelled, either explicitly or by a newer request
+    /// with the same request key
+    #[error("request cancelled")]
+    Cancelled,
+
+    /// A middleware rejected the request or response
+    #[error("middleware error: {0}")]
+    Middleware(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    /// The realtime connection did not behave as expected
+    #[error("realtime error: {0}")]
+    Realtime(String),
+
+    /// The first factor was accepted, finish with a second one sent with
+    /// this `mfa_id`
+    #[error("multi-factor authentication required")]
+    MfaRequired { mfa_id: String },
+
+    /// The one-time password was wrong, expired or already used
+    #[error("invalid or expired one-time password")]
+    InvalidOtp(#[source] ApiError),
+
+    /// An OAuth2 sign in was denied or its callback did not check out
+    #[error("oauth2 error: {0}")]
+    OAuth2(String),
+
+    /// A local file could not be read or written
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    /// HTTP status of the failed response, if PocketBase answered at all.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Api(e) | Error::InvalidOtp(e) => Some(e.status),
+            Error::Transport(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    pub fn is_unauthorized(&self) -> bool {
+        self.status() == Some(401)
+    }
+
+    pub fn is_forbidden(&self) -> bool {
+        self.status() == Some(403)
+    }
+
+    /// `true` for a 400 response carrying per-field errors in `data`.
+    pub fn is_validation(&self) -> bool {
+        self.validation_errors().is_some()
+    }
+
+    /// Per-field errors of a failed create or update, if any.
+    pub fn validation_errors(&self) -> Option<ValidationErrors> {
+        match self {
+            Error::Api(e) if e.status == 400 => {
+                Some(e.validation_errors()).filter(|errors| !errors.is_empty())
+            }
+            _ => None,
+        }
+    }
+
+    /// `true` when a confirm call rejected its token, e.g. because it expired
+    /// or was already used.
+    pub fn is_invalid_token(&self) -> bool {
+        self.validation_errors()
+            .is_some_and(|errors| errors.contains("token"))
+    }
+
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::Api(e) | Error::InvalidOtp(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Error body returned by PocketBase for non-2xx responses.
+#[derive(Debug, Clone, Deserialize, thiserror::Error)]
+#[error("{status} {message}")]
+pub struct ApiError {
+    #[serde(default)]
+    pub status: u16,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub data: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FieldError {
+    pub code: String,
+    pub message: String,
+}
+
+impl ApiError {
+    pub(crate) fn from_body(status: StatusCode, body: &[u8]) -> Self {
+        let mut error = serde_json::from_slice::<ApiError>(body).unwrap_or_else(|_| ApiError {
+            status: 0,
+            message: String::from_utf8_lossy(body).trim().to_string(),
+            data: Map::new(),
+        });
+
+        // A first factor that still needs MFA is answered with a bare
+        // `{"mfaId": ..}`, keep it around for the auth calls.
+        if let Ok(Value::Object(body)) = serde_json::from_slice::<Value>(body)
+            && let Some(mfa_id) = body.get("mfaId")
+        {
+            error.data.insert("mfaId".to_string(), mfa_id.clone());
+        }
+
+        error.status = status.as_u16();
+        if error.message.is_empty() {
+            error.message = status.canonical_reason().unwrap_or_default().to_string();
+        }
+
+        error
+    }
+
+    /// Top level entries of `data` shaped as `{ "code": .., "message": .. }`.
+    pub fn field_errors(&self) -> HashMap<String, FieldError> {
+        self.data
+            .iter()
+            .filter_map(|(field, value)| {
+                serde_json::from_value::<FieldError>(value.clone())
+                    .ok()
+                    .map(|e| (field.clone(), e))
+            })
+            .collect()
+    }
+
+    /// All field errors in `data`, nested objects included.
+    pub fn validation_errors(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+        for (key, value) in &self.data {
+            errors.collect(key.clone(), value);
+        }
+        errors
+    }
+}
+
+/// Field errors keyed by their path in the submitted payload.
+///
+/// Nested keys are joined with `.`, so an error on the second entry of a
+/// `tags` array is found under `tags.1`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    errors: BTreeMap<String, FieldError>,
+}
+
+impl ValidationErrors {
+    fn collect(&mut self, path: String, value: &Value) {
+        if let Ok(error) = serde_json::from_value::<FieldError>(value.clone()) {
+            self.errors.insert(path, error);
+            return;
+        }
+
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    self.collect(format!("{}.{}", path, key), value);
+                }
+            }
+            Value::Array(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    self.collect(format!("{}.{}", path, index), value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn get(&self, field: &str) -> Option<&FieldError> {
+        self.errors.get(field)
+    }
+
+    pub fn contains(&self, field: &str) -> bool {
+        self.errors.contains_key(field)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FieldError)> {
+        self.errors
+            .iter()
+            .map(|(field, error)| (field.as_str(), error))
+    }
+
+    /// Errors below `prefix`, with the prefix stripped from their keys.
+    pub fn nested(&self, prefix: &str) -> ValidationErrors {
+        let prefix = format!("{}.", prefix);
+        ValidationErrors {
+            errors: self
+                .errors
+                .iter()
+                .filter_map(|(field, error)| {
+                    field
+                        .strip_prefix(&prefix)
+                        .map(|field| (field.to_string(), error.clone()))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (field, error) in &self.errors {
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", field, error.code)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a ValidationErrors {
+    type Item = (&'a String, &'a FieldError);
+    type IntoIter = std::collections::btree_map::Iter<'a, String, FieldError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
@@ -0,0 +1,478 @@
+use crate::auth::AuthenticatedRequest;
+use crate::error::{Error, Result};
+use crate::httpc::{HttpClient, HttpRequest, RequestKey};
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use reqwest::header::{CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Body, Method, Response, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+type Reader = Pin<Box<dyn AsyncRead + Send + Sync>>;
+
+#[derive(Clone)]
+enum FileSource {
+    Bytes(Vec<u8>),
+    Path(PathBuf),
+    Reader(Arc<Mutex<Option<Reader>>>),
+}
+
+/// A file sent to a record `file` field.
+#[derive(Clone)]
+pub struct FileUpload {
+    source: FileSource,
+    file_name: Option<String>,
+    content_type: Option<String>,
+}
+
+impl fmt::Debug for FileUpload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match &self.source {
+            FileSource::Bytes(bytes) => format!("{} bytes", bytes.len()),
+            FileSource::Path(path) => path.display().to_string(),
+            FileSource::Reader(_) => "reader".to_string(),
+        };
+        f.debug_struct("FileUpload")
+            .field("source", &source)
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .finish()
+    }
+}
+
+impl FileUpload {
+    pub fn bytes(bytes: impl Into<Vec<u8>>, file_name: &str) -> Self {
+        Self {
+            source: FileSource::Bytes(bytes.into()),
+            file_name: Some(file_name.to_string()),
+            content_type: None,
+        }
+    }
+
+    /// Reads the file when the request is sent. The file name and content
+    /// type default to the ones derived from the path.
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: FileSource::Path(path.into()),
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    /// Streams the file from `reader`. The reader is consumed by the first
+    /// request that sends it.
+    pub fn reader(reader: impl AsyncRead + Send + Sync + 'static, file_name: &str) -> Self {
+        Self {
+            source: FileSource::Reader(Arc::new(Mutex::new(Some(Box::pin(reader))))),
+            file_name: Some(file_name.to_string()),
+            content_type: None,
+        }
+    }
+
+    pub fn file_name(self, file_name: &str) -> Self {
+        Self {
+            file_name: Some(file_name.to_string()),
+            ..self
+        }
+    }
+
+    pub fn content_type(self, content_type: &str) -> Self {
+        Self {
+            content_type: Some(content_type.to_string()),
+            ..self
+        }
+    }
+
+    async fn into_part(self) -> Result<Part> {
+        let part = match self.source {
+            FileSource::Bytes(bytes) => Part::bytes(bytes),
+            FileSource::Path(path) => Part::file(path).await?,
+            FileSource::Reader(reader) => {
+                let reader = reader
+                    .lock()
+                    .unwrap()
+                    .take()
+                    .ok_or_else(|| Error::Config("file reader was already sent".to_string()))?;
+                Part::stream(Body::wrap_stream(ReaderStream::new(reader)))
+            }
+        };
+        let part = match self.file_name {
+            Some(file_name) => part.file_name(file_name),
+            None => part,
+        };
+
+        match self.content_type {
+            Some(content_type) => part
+                .mime_str(&content_type)
+                .map_err(|_| Error::Config(format!("invalid content type `{}`", content_type))),
+            None => Ok(part),
+        }
+    }
+}
+
+/// Files queued on a record create or update, including the `field+` and
+/// `field-` modifiers of multi-file fields.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileFields {
+    uploads: Vec<(String, FileUpload)>,
+    removed: BTreeMap<String, Vec<String>>,
+}
+
+impl FileFields {
+    pub(crate) fn set(&mut self, field: &str, file: FileUpload) {
+        self.uploads.push((field.to_string(), file));
+    }
+
+    pub(crate) fn append(&mut self, field: &str, file: FileUpload) {
+        self.uploads.push((format!("{}+", field), file));
+    }
+
+    pub(crate) fn remove(&mut self, field: &str, file_name: &str) {
+        self.removed
+            .entry(format!("{}-", field))
+            .or_default()
+            .push(file_name.to_string());
+    }
+
+    /// Builds the request carrying `data`. Plain JSON is sent unless files are
+    /// attached, in which case the fields travel as the `@jsonPayload` part
+    /// of a `multipart/form-data` body.
+    pub(crate) async fn request<T: Serialize>(
+        &self,
+        client: &HttpClient,
+        method: Method,
+        url: &str,
+        data: &T,
+    ) -> Result<HttpRequest> {
+        let mut payload = serde_json::to_value(data).map_err(Error::Encode)?;
+        if !self.removed.is_empty() {
+            let Value::Object(fields) = &mut payload else {
+                return Err(Error::Config(
+                    "record data must serialize to a JSON object".to_string(),
+                ));
+            };
+            for (field, file_names) in &self.removed {
+                fields.insert(field.clone(), file_names.clone().into());
+            }
+        }
+        let payload = serde_json::to_string(&payload).map_err(Error::Encode)?;
+
+        if self.uploads.is_empty() {
+            return Ok(client.request(method, url).map(|builder| {
+                builder
+                    .header("Content-Type", "application/json")
+                    .body(payload)
+            }));
+        }
+
+        let mut form = Form::new().text("@jsonPayload", payload);
+        for (field, file) in self.uploads.clone() {
+            form = form.part(field, file.into_part().await?);
+        }
+
+        Ok(client
+            .request(method, url)
+            .map(|builder| builder.multipart(form)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FilesManager<'a> {
+    pub(crate) client: &'a HttpClient,
+    pub(crate) token: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FileTokenResponse {
+    token: String,
+}
+
+/// A record files can be stored on, see [`FilesManager::url`].
+pub trait FileOwner {
+    fn record_id(&self) -> &str;
+
+    /// Collection id, or the collection name if the id was not loaded.
+    fn collection(&self) -> &str;
+}
+
+/// Url of a file stored on a record, e.g. for rendering images.
+#[derive(Debug, Clone)]
+pub struct FileUrlBuilder<'a> {
+    pub(crate) client: &'a HttpClient,
+    pub(crate) token: Option<&'a str>,
+    pub collection: String,
+    pub record_id: String,
+    pub filename: String,
+    pub thumb: Option<String>,
+    pub download: bool,
+    pub file_token: Option<String>,
+}
+
+impl<'a> FilesManager<'a> {
+    /// Uses the collection id of `record`, or its collection name if the id
+    /// was not loaded.
+    pub fn url(&self, record: &impl FileOwner, filename: &str) -> FileUrlBuilder<'a> {
+        FileUrlBuilder {
+            client: self.client,
+            token: self.token,
+            collection: record.collection().to_string(),
+            record_id: record.record_id().to_string(),
+            filename: filename.to_string(),
+            thumb: None,
+            download: false,
+            file_token: None,
+        }
+    }
+
+    pub fn download(&self, record: &impl FileOwner, filename: &str) -> FileDownloadBuilder<'a> {
+        FileDownloadBuilder {
+            url: self.url(record, filename),
+            range: None,
+            protected: false,
+        }
+    }
+
+    /// Short-lived token granting access to protected files, requested on
+    /// behalf of the authenticated record.
+    pub async fn get_token(&self) -> Result<String> {
+        let response = self
+            .client
+            .post("/api/files/token", String::new())
+            .route("/api/files/token", None)
+            .request_key(&RequestKey::Disabled)
+            .try_attach_auth_info(self.token)
+            .fetch::<FileTokenResponse>()
+            .await?;
+
+        Ok(response.token)
+    }
+}
+
+impl FileUrlBuilder<'_> {
+    /// Thumbnail size such as `100x100`, `0x300`, `100x0`, or a crop mode
+    /// suffixed size like `100x100t`, `100x100b` and `100x100f`. Only image
+    /// files with the size listed in the field options are resized.
+    pub fn thumb(&self, thumb: &str) -> Self {
+        Self {
+            thumb: Some(thumb.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn download(&self, download: bool) -> Self {
+        Self {
+            download,
+            ..self.clone()
+        }
+    }
+
+    pub fn file_token(&self, file_token: &str) -> Self {
+        Self {
+            file_token: Some(file_token.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn build(&self) -> Result<String> {
+        let base_url = self.client.base_url().trim_end_matches('/');
+        let query = self.query();
+        let url = if query.is_empty() {
+            Url::parse(&format!("{}{}", base_url, self.path()))
+        } else {
+            Url::parse_with_params(&format!("{}{}", base_url, self.path()), query)
+        };
+
+        url.map(String::from)
+            .map_err(|_| Error::Config(format!("invalid base url `{}`", base_url)))
+    }
+
+    /// Builds the url with a freshly requested file token, for files in
+    /// protected fields.
+    pub async fn build_with_token(&self) -> Result<String> {
+        let file_token = FilesManager {
+            client: self.client,
+            token: self.token,
+        }
+        .get_token()
+        .await?;
+
+        self.file_token(&file_token).build()
+    }
+
+    pub(crate) fn path(&self) -> String {
+        format!(
+            "/api/files/{}/{}/{}",
+            utf8_percent_encode(&self.collection, PATH_SEGMENT),
+            utf8_percent_encode(&self.record_id, PATH_SEGMENT),
+            utf8_percent_encode(&self.filename, PATH_SEGMENT)
+        )
+    }
+
+    pub(crate) fn query(&self) -> Vec<(&str, &str)> {
+        let mut query = vec![];
+        if let Some(thumb) = &self.thumb {
+            query.push(("thumb", thumb.as_str()));
+        }
+        if self.download {
+            query.push(("download", "1"));
+        }
+        if let Some(file_token) = &self.file_token {
+            query.push(("token", file_token.as_str()));
+        }
+        query
+    }
+}
+
+/// Streams a file through the client, sharing its base url, auth,
+/// middlewares and error handling.
+#[derive(Debug, Clone)]
+pub struct FileDownloadBuilder<'a> {
+    pub(crate) url: FileUrlBuilder<'a>,
+    pub range: Option<(u64, Option<u64>)>,
+    pub protected: bool,
+}
+
+/// A successful download whose body has not been read yet.
+#[derive(Debug)]
+pub struct FileDownload {
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub content_range: Option<String>,
+    response: Response,
+}
+
+impl FileDownloadBuilder<'_> {
+    pub fn thumb(&self, thumb: &str) -> Self {
+        Self {
+            url: self.url.thumb(thumb),
+            ..self.clone()
+        }
+    }
+
+    pub fn file_token(&self, file_token: &str) -> Self {
+        Self {
+            url: self.url.file_token(file_token),
+            ..self.clone()
+        }
+    }
+
+    /// Requests a file token before downloading, for files in protected
+    /// fields.
+    pub fn protected(&self, protected: bool) -> Self {
+        Self {
+            protected,
+            ..self.clone()
+        }
+    }
+
+    /// Byte range to download, `end` being inclusive. Open ended when `end`
+    /// is `None`.
+    pub fn range(&self, start: u64, end: Option<u64>) -> Self {
+        Self {
+            range: Some((start, end)),
+            ..self.clone()
+        }
+    }
+
+    pub async fn send(&self) -> Result<FileDownload> {
+        let url = if self.protected && self.url.file_token.is_none() {
+            let file_token = FilesManager {
+                client: self.url.client,
+                token: self.url.token,
+            }
+            .get_token()
+            .await?;
+            self.url.file_token(&file_token)
+        } else {
+            self.url.clone()
+        };
+        let range = self.range.map(|(start, end)| match end {
+            Some(end) => format!("bytes={}-{}", start, end),
+            None => format!("bytes={}-", start),
+        });
+
+        let response = url
+            .client
+            .get(&url.path(), Some(url.query()))
+            .route("/api/files/{collection}/{record}/{filename}", None)
+            .request_key(&RequestKey::Disabled)
+            .streaming()
+            .try_attach_auth_info(url.token)
+            .map(|builder| match range {
+                Some(range) => builder.header(RANGE, range),
+                None => builder,
+            })
+            .send()
+            .await?;
+
+        Ok(FileDownload::new(response))
+    }
+
+    /// Downloads the file into `writer` and returns the number of bytes
+    /// written.
+    pub async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<u64> {
+        self.send().await?.write_to(writer).await
+    }
+}
+
+impl FileDownload {
+    fn new(response: Response) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        Self {
+            content_length: response.content_length(),
+            content_type: header(CONTENT_TYPE),
+            content_range: header(CONTENT_RANGE),
+            response,
+        }
+    }
+
+    /// Whether the server answered a range request with part of the file.
+    pub fn is_partial(&self) -> bool {
+        self.response.status() == StatusCode::PARTIAL_CONTENT
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = Result<Bytes>> {
+        self.response
+            .bytes_stream()
+            .map(|chunk| chunk.map_err(Error::Transport))
+    }
+
+    pub async fn bytes(self) -> Result<Bytes> {
+        Ok(self.response.bytes().await?)
+    }
+
+    pub async fn write_to<W: AsyncWrite + Unpin>(self, writer: &mut W) -> Result<u64> {
+        let mut written = 0;
+        let mut stream = std::pin::pin!(self.into_stream());
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+}
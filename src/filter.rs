@@ -0,0 +1,401 @@
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A filter expression, accepted by every `filter` method of the crate.
+///
+/// ```
+/// use pocketbase_sdk::filter::{field, request};
+///
+/// let filter = field("status")
+///     .eq("active")
+///     .and(field("author").eq(request("auth.id")));
+/// assert_eq!(filter.to_string(), "status = 'active' && author = @request.auth.id");
+/// ```
+///
+/// Values that have no filter literal, such as text ending in a backslash or
+/// `NaN`, make the filter invalid and the request fails with
+/// [`Error::Query`] before it is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    expr: String,
+    kind: Kind,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Single,
+    And,
+    Or,
+    /// Written by hand, so its precedence is unknown.
+    Raw,
+}
+
+/// A field, or a macro such as `@request.auth.id`, on either side of a
+/// comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field(String);
+
+/// A value compared against a field, rendered as a filter literal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Null,
+    Bool(bool),
+    /// Any integer, only those fitting in an `i64` or `u64` have a literal.
+    Int(i128),
+    Float(f64),
+    Text(String),
+    Field(Field),
+}
+
+pub fn field(name: &str) -> Field {
+    Field(name.to_string())
+}
+
+/// `@request.*` macro, e.g. `request("auth.id")`.
+pub fn request(path: &str) -> Field {
+    Field(format!("@request.{}", path))
+}
+
+/// `@collection.*` macro for joining other collections, e.g.
+/// `collection("memberships.user")`.
+pub fn collection(path: &str) -> Field {
+    Field(format!("@collection.{}", path))
+}
+
+impl Field {
+    fn compare(&self, op: &str, value: impl Into<FilterValue>) -> Filter {
+        match value.into().literal() {
+            Ok(value) => Filter {
+                expr: format!("{} {} {}", self.0, op, value),
+                kind: Kind::Single,
+                error: None,
+            },
+            Err(reason) => Filter::invalid(format!("{} {}", self.0, op), reason),
+        }
+    }
+
+    pub fn eq(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("=", value)
+    }
+
+    pub fn ne(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("!=", value)
+    }
+
+    pub fn gt(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(">", value)
+    }
+
+    pub fn gte(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(">=", value)
+    }
+
+    pub fn lt(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("<", value)
+    }
+
+    pub fn lte(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("<=", value)
+    }
+
+    /// `~`, matching values that contain `value`. `%` wildcards are kept.
+    pub fn like(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("~", value)
+    }
+
+    pub fn not_like(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("!~", value)
+    }
+
+    /// `?=`, true if any item of a multi-valued field equals `value`.
+    pub fn any_eq(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?=", value)
+    }
+
+    pub fn any_ne(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?!=", value)
+    }
+
+    pub fn any_gt(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?>", value)
+    }
+
+    pub fn any_gte(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?>=", value)
+    }
+
+    pub fn any_lt(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?<", value)
+    }
+
+    pub fn any_lte(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?<=", value)
+    }
+
+    pub fn any_like(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?~", value)
+    }
+
+    pub fn any_not_like(&self, value: impl Into<FilterValue>) -> Filter {
+        self.compare("?!~", value)
+    }
+}
+
+impl Filter {
+    /// Uses `expr` verbatim. It is parenthesized when combined with other
+    /// filters.
+    pub fn raw(expr: &str) -> Self {
+        Self {
+            expr: expr.to_string(),
+            kind: Kind::Raw,
+            error: None,
+        }
+    }
+
+    /// Replaces `{:name}` placeholders in `expr` with the escaped `params`,
+    /// like `pb.filter()` in the JS SDK. Unknown placeholders are left as is.
+    ///
+    /// ```
+    /// use pocketbase_sdk::filter::Filter;
+    ///
+    /// let filter = Filter::bind("title ~ {:title} && views > {:views}", [
+    ///     ("title", "it's".into()),
+    ///     ("views", 10.into()),
+    /// ]);
+    /// assert_eq!(filter.to_string(), r"title ~ 'it\'s' && views > 10");
+    /// ```
+    pub fn bind<'k>(expr: &str, params: impl IntoIterator<Item = (&'k str, FilterValue)>) -> Self {
+        let params: HashMap<&str, String> = match params
+            .into_iter()
+            .map(|(name, value)| Ok((name, value.literal()?)))
+            .collect::<Result<_, String>>()
+        {
+            Ok(params) => params,
+            Err(reason) => return Self::invalid(expr.to_string(), reason),
+        };
+
+        // Filled in a single pass, so placeholders inside values stay text.
+        let mut bound = String::with_capacity(expr.len());
+        let mut rest = expr;
+        while let Some(start) = rest.find("{:") {
+            bound.push_str(&rest[..start]);
+            let token = &rest[start..];
+            let value = token
+                .find('}')
+                .and_then(|end| Some((params.get(&token[2..end])?, end)));
+            match value {
+                Some((value, end)) => {
+                    bound.push_str(value);
+                    rest = &token[end + 1..];
+                }
+                None => {
+                    bound.push_str("{:");
+                    rest = &token[2..];
+                }
+            }
+        }
+        bound.push_str(rest);
+
+        Self {
+            expr: bound,
+            kind: Kind::Raw,
+            error: None,
+        }
+    }
+
+    pub fn and(self, other: Filter) -> Self {
+        Self {
+            expr: format!(
+                "{} && {}",
+                self.operand(Kind::And),
+                other.operand(Kind::And)
+            ),
+            kind: Kind::And,
+            error: self.error.or(other.error),
+        }
+    }
+
+    pub fn or(self, other: Filter) -> Self {
+        Self {
+            expr: format!("{} || {}", self.operand(Kind::Or), other.operand(Kind::Or)),
+            kind: Kind::Or,
+            error: self.error.or(other.error),
+        }
+    }
+
+    /// Wraps the expression in parentheses.
+    pub fn group(self) -> Self {
+        Self {
+            expr: format!("({})", self.expr),
+            kind: Kind::Single,
+            error: self.error,
+        }
+    }
+
+    /// The rendered expression, or [`Error::Query`] if a value could not be
+    /// represented.
+    pub(crate) fn validate(&self) -> Result<String> {
+        match &self.error {
+            Some(reason) => Err(Error::Query(format!("invalid filter: {}", reason))),
+            None => Ok(self.expr.clone()),
+        }
+    }
+
+    fn invalid(expr: String, reason: String) -> Self {
+        Self {
+            expr,
+            kind: Kind::Raw,
+            error: Some(reason),
+        }
+    }
+
+    fn operand(&self, parent: Kind) -> String {
+        match (self.kind, parent) {
+            (Kind::Or, Kind::And) | (Kind::Raw, _) => format!("({})", self.expr),
+            _ => self.expr.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+impl From<&str> for Filter {
+    fn from(expr: &str) -> Self {
+        Filter::raw(expr)
+    }
+}
+
+impl From<String> for Filter {
+    fn from(expr: String) -> Self {
+        Filter::raw(&expr)
+    }
+}
+
+impl From<&String> for Filter {
+    fn from(expr: &String) -> Self {
+        Filter::raw(expr)
+    }
+}
+
+impl FilterValue {
+    fn literal(&self) -> Result<String, String> {
+        match self {
+            FilterValue::Null => Ok("null".to_string()),
+            FilterValue::Bool(value) => Ok(value.to_string()),
+            FilterValue::Int(value) if (i64::MIN as i128..=u64::MAX as i128).contains(value) => {
+                Ok(value.to_string())
+            }
+            FilterValue::Int(value) => Err(format!("`{}` does not fit in 64 bits", value)),
+            FilterValue::Float(value) if value.is_finite() => Ok(value.to_string()),
+            FilterValue::Float(value) => Err(format!("`{}` is not a number literal", value)),
+            // PocketBase only unescapes `\'` and reads any quote after a
+            // backslash as escaped, so a trailing backslash would swallow the
+            // closing quote.
+            FilterValue::Text(value) if value.ends_with('\\') => Err(format!(
+                "text `{}` ends with a backslash, which can not be escaped",
+                value
+            )),
+            FilterValue::Text(value) => Ok(format!("'{}'", value.replace('\'', "\\'"))),
+            FilterValue::Field(field) => Ok(field.0.clone()),
+        }
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        FilterValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        FilterValue::Text(value)
+    }
+}
+
+impl From<&String> for FilterValue {
+    fn from(value: &String) -> Self {
+        FilterValue::Text(value.clone())
+    }
+}
+
+impl From<bool> for FilterValue {
+    fn from(value: bool) -> Self {
+        FilterValue::Bool(value)
+    }
+}
+
+macro_rules! number_value {
+    ($variant:ident as $as:ty: $($ty:ty),*) => {
+        $(
+            impl From<$ty> for FilterValue {
+                fn from(value: $ty) -> Self {
+                    FilterValue::$variant(value as $as)
+                }
+            }
+        )*
+    };
+}
+
+number_value!(Int as i128: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+number_value!(Float as f64: f64);
+
+impl From<u128> for FilterValue {
+    fn from(value: u128) -> Self {
+        FilterValue::Int(i128::try_from(value).unwrap_or(i128::MAX))
+    }
+}
+
+impl From<f32> for FilterValue {
+    /// Keeps the shortest decimal form of `value`, e.g. `0.1` rather than
+    /// `0.10000000149011612`.
+    fn from(value: f32) -> Self {
+        FilterValue::Float(value.to_string().parse().unwrap_or(f64::NAN))
+    }
+}
+
+impl From<DateTime<Utc>> for FilterValue {
+    fn from(value: DateTime<Utc>) -> Self {
+        FilterValue::Text(value.format("%Y-%m-%d %H:%M:%S%.3fZ").to_string())
+    }
+}
+
+impl From<Value> for FilterValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => FilterValue::Null,
+            Value::Bool(value) => FilterValue::Bool(value),
+            Value::Number(value) => {
+                if let Some(number) = value.as_i64() {
+                    FilterValue::Int(number.into())
+                } else if let Some(number) = value.as_u64() {
+                    FilterValue::Int(number.into())
+                } else {
+                    FilterValue::Float(value.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            Value::String(value) => FilterValue::Text(value),
+            value => FilterValue::Text(value.to_string()),
+        }
+    }
+}
+
+impl From<Field> for FilterValue {
+    fn from(field: Field) -> Self {
+        FilterValue::Field(field)
+    }
+}
+
+impl<T: Into<FilterValue>> From<Option<T>> for FilterValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(FilterValue::Null, Into::into)
+    }
+}
@@ -1,19 +1,200 @@
-use reqwest::RequestBuilder;
+use crate::error::{ApiError, Error, Result};
+use crate::middleware::Middlewares;
+use crate::retry::RetryPolicy;
+use crate::trace::{self, Route};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
 
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     base_url: String,
     client: reqwest::Client,
+    timeout: Option<Duration>,
+    headers: HeaderMap,
+    retry_policy: RetryPolicy,
+    middlewares: Middlewares,
+    cancellations: Cancellations,
+}
+
+/// Key under which a pending request can be cancelled, either explicitly or
+/// by a newer request with the same key.
+#[derive(Debug, Clone, Default)]
+pub(crate) enum RequestKey {
+    /// `METHOD /path`, without the query string, for GET and HEAD only
+    #[default]
+    Auto,
+    Custom(String),
+    Disabled,
+}
+
+impl RequestKey {
+    pub(crate) fn from_option(key: Option<&str>) -> Self {
+        match key {
+            Some(key) => RequestKey::Custom(key.to_string()),
+            None => RequestKey::Disabled,
+        }
+    }
+}
+
+type PendingRequests = HashMap<String, Vec<(u64, oneshot::Sender<()>)>>;
+
+/// Pending requests by request key. Dropping a sender cancels the request
+/// waiting on the matching receiver.
+#[derive(Debug, Clone)]
+struct Cancellations {
+    enabled: bool,
+    next_id: Arc<AtomicU64>,
+    pending: Arc<Mutex<PendingRequests>>,
+}
+
+/// Removes its request from the pending ones when dropped, also if the
+/// request future is dropped before it completes.
+#[derive(Default)]
+struct CancelGuard {
+    registration: Option<(Cancellations, String, u64)>,
+    cancelled: Option<oneshot::Receiver<()>>,
+}
+
+impl Cancellations {
+    fn new() -> Self {
+        Self {
+            enabled: false,
+            next_id: Arc::new(AtomicU64::new(0)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Registers a request under `key`. With auto-cancellation enabled it
+    /// replaces, and so cancels, the pending requests with the same key.
+    fn register(&self, key: String) -> CancelGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        let mut pending = self.pending.lock().unwrap();
+        if self.enabled {
+            pending.insert(key.clone(), vec![(id, sender)]);
+        } else {
+            pending.entry(key.clone()).or_default().push((id, sender));
+        }
+
+        CancelGuard {
+            registration: Some((self.clone(), key, id)),
+            cancelled: Some(receiver),
+        }
+    }
+
+    fn cancel(&self, key: &str) {
+        self.pending.lock().unwrap().remove(key);
+    }
+
+    fn cancel_all(&self) {
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+impl CancelGuard {
+    async fn run<T>(mut self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        let Some(cancelled) = self.cancelled.take() else {
+            return future.await;
+        };
+
+        tokio::select! {
+            result = future => result,
+            _ = cancelled => Err(Error::Cancelled),
+        }
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        let Some((cancellations, key, id)) = &self.registration else {
+            return;
+        };
+
+        let mut pending = cancellations.pending.lock().unwrap();
+        if let Some(requests) = pending.get_mut(key) {
+            requests.retain(|(pending_id, _)| pending_id != id);
+            if requests.is_empty() {
+                pending.remove(key);
+            }
+        }
+    }
+}
+
+/// A request on its way to PocketBase. Sending it maps non-2xx responses
+/// to [`Error::Api`].
+#[derive(Debug)]
+pub struct HttpRequest {
+    method: Method,
+    builder: RequestBuilder,
+    timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    middlewares: Middlewares,
+    route: Route,
+    path: String,
+    request_key: RequestKey,
+    cancellations: Cancellations,
 }
 
 impl HttpClient {
     pub fn new(base_url: &str) -> Self {
+        Self::with_client(base_url, reqwest::Client::new())
+    }
+
+    pub fn with_client(base_url: &str, client: reqwest::Client) -> Self {
         Self {
             base_url: base_url.to_string(),
-            client: reqwest::Client::new(),
+            client,
+            timeout: None,
+            headers: HeaderMap::new(),
+            retry_policy: RetryPolicy::none(),
+            middlewares: Middlewares::default(),
+            cancellations: Cancellations::new(),
+        }
+    }
+
+    pub fn timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub fn headers(self, headers: HeaderMap) -> Self {
+        Self { headers, ..self }
+    }
+
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    pub(crate) fn middlewares(self, middlewares: Middlewares) -> Self {
+        Self {
+            middlewares,
+            ..self
         }
     }
 
+    /// Whether a request cancels a pending one with the same request key.
+    pub fn auto_cancellation(mut self, enabled: bool) -> Self {
+        self.cancellations.enabled = enabled;
+        self
+    }
+
+    pub fn cancel(&self, request_key: &str) {
+        self.cancellations.cancel(request_key);
+    }
+
+    pub fn cancel_all(&self) {
+        self.cancellations.cancel_all();
+    }
+
     pub fn base_url(&self) -> &str {
         &self.base_url
     }
@@ -22,31 +203,202 @@ impl HttpClient {
         format!("{}{}", self.base_url, path)
     }
 
-    pub fn get(&self, url: &str, query_params: Option<Vec<(&str, &str)>>) -> RequestBuilder {
-        let mut builder = self.client.get(self.build_url(url));
+    pub fn request(&self, method: Method, path: &str) -> HttpRequest {
+        HttpRequest {
+            builder: self
+                .client
+                .request(method.clone(), self.build_url(path))
+                .headers(self.headers.clone()),
+            method,
+            timeout: self.timeout,
+            retry_policy: self.retry_policy.clone(),
+            middlewares: self.middlewares.clone(),
+            route: Route::default(),
+            path: path.to_string(),
+            request_key: RequestKey::Auto,
+            cancellations: self.cancellations.clone(),
+        }
+    }
+
+    pub fn get(&self, url: &str, query_params: Option<Vec<(&str, &str)>>) -> HttpRequest {
+        let request = self.request(Method::GET, url);
 
-        if let Some(pairs) = query_params {
-            builder = builder.query(pairs.as_slice());
+        match query_params {
+            Some(pairs) => request.map(|builder| builder.query(pairs.as_slice())),
+            None => request,
         }
+    }
+
+    pub fn post(&self, url: &str, body_content: String) -> HttpRequest {
+        self.request(Method::POST, url).map(|builder| {
+            builder
+                .header("Content-Type", "application/json")
+                .body(body_content)
+        })
+    }
 
-        builder
+    pub fn delete(&self, url: &str) -> HttpRequest {
+        self.request(Method::DELETE, url)
     }
 
-    pub fn post(&self, url: &str, body_content: String) -> RequestBuilder {
-        self.client
-            .post(self.build_url(url))
-            .header("Content-Type", "application/json")
-            .body(body_content)
+    pub fn patch(&self, url: &str, body_content: String) -> HttpRequest {
+        self.request(Method::PATCH, url).map(|builder| {
+            builder
+                .header("Content-Type", "application/json")
+                .body(body_content)
+        })
     }
+}
 
-    pub fn delete(&self, url: &str) -> RequestBuilder {
-        self.client.delete(self.build_url(url))
+impl HttpRequest {
+    pub fn map(self, f: impl FnOnce(RequestBuilder) -> RequestBuilder) -> Self {
+        Self {
+            builder: f(self.builder),
+            ..self
+        }
+    }
+
+    /// Names the request for tracing, e.g.
+    /// `/api/collections/{collection}/records/{id}`.
+    pub fn route(self, template: &'static str, collection: Option<&str>) -> Self {
+        Self {
+            route: Route {
+                template: Some(template),
+                collection: collection.map(str::to_string),
+            },
+            ..self
+        }
     }
 
-    pub fn patch(&self, url: &str, body_content: String) -> RequestBuilder {
-        self.client
-            .patch(self.build_url(url))
-            .header("Content-Type", "application/json")
-            .body(body_content)
+    pub(crate) fn request_key(self, request_key: &RequestKey) -> Self {
+        Self {
+            request_key: request_key.clone(),
+            ..self
+        }
+    }
+
+    /// Custom keys can always be cancelled explicitly, automatic ones only
+    /// exist with auto-cancellation enabled.
+    fn cancel_guard(&self) -> CancelGuard {
+        let key = match &self.request_key {
+            // A cancelled write may still have been applied, so only reads
+            // are keyed unless the caller asks for it.
+            RequestKey::Auto
+                if self.cancellations.enabled
+                    && matches!(self.method, Method::GET | Method::HEAD) =>
+            {
+                format!("{} {}", self.method, self.path)
+            }
+            RequestKey::Auto | RequestKey::Disabled => return CancelGuard::default(),
+            RequestKey::Custom(key) => key.clone(),
+        };
+
+        self.cancellations.register(key)
+    }
+
+    /// Lifts the client wide timeout, for responses that stay open.
+    pub fn streaming(self) -> Self {
+        Self {
+            timeout: None,
+            ..self
+        }
+    }
+
+    pub async fn send(self) -> Result<Response> {
+        self.cancel_guard().run(self.dispatch()).await
+    }
+
+    async fn dispatch(self) -> Result<Response> {
+        let span = trace::request(&self.method, &self.route);
+        trace::instrument(&span, self.send_in_span(&span)).await
+    }
+
+    async fn send_in_span(self, span: &trace::Span) -> Result<Response> {
+        let started = Instant::now();
+        let max_attempts = if self.retry_policy.allows(&self.method) {
+            self.retry_policy.max_attempts
+        } else {
+            1
+        };
+
+        let mut pending = self.builder;
+        let mut attempt = 1;
+
+        let response = loop {
+            let retry = if attempt < max_attempts {
+                pending.try_clone()
+            } else {
+                None
+            };
+
+            let builder = match self.timeout {
+                Some(timeout) => pending.timeout(timeout),
+                None => pending,
+            };
+            let outcome = execute(&self.middlewares, builder).await?;
+
+            if let Some(next) = retry
+                && let Some(delay) = self.retry_policy.delay(attempt, &outcome)
+            {
+                tokio::time::sleep(delay).await;
+                pending = next;
+                attempt += 1;
+                continue;
+            }
+
+            trace::finish(
+                span,
+                outcome.as_ref().ok().map(Response::status),
+                started,
+                attempt - 1,
+            );
+            break outcome?;
+        };
+
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        let body = self.middlewares.transform_body(status, &headers, body)?;
+        Err(ApiError::from_body(status, &body).into())
+    }
+
+    pub async fn fetch<T: DeserializeOwned>(self) -> Result<T> {
+        let guard = self.cancel_guard();
+        let middlewares = self.middlewares.clone();
+        guard
+            .run(async move {
+                let response = self.dispatch().await?;
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = response.bytes().await?;
+                let body = middlewares.transform_body(status, &headers, body)?;
+                serde_json::from_slice(&body).map_err(Error::Decode)
+            })
+            .await
+    }
+}
+
+/// Sends a single attempt. Middleware failures are returned as the outer
+/// error and end the call, transport failures are left to the retry policy.
+async fn execute(
+    middlewares: &Middlewares,
+    builder: RequestBuilder,
+) -> Result<std::result::Result<Response, reqwest::Error>> {
+    let (client, request) = builder.build_split();
+    let mut request = match request {
+        Ok(request) => request,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    middlewares.before_send(&mut request)?;
+
+    match client.execute(request).await {
+        Ok(response) => Ok(Ok(middlewares.after_send(response)?)),
+        Err(e) => Ok(Err(e)),
     }
 }
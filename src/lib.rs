@@ -18,4 +18,38 @@ pub mod realtime;
 
 pub mod auth;
 
+/// Error Types
+pub mod error;
+
+/// Retry Policies
+pub mod retry;
+
+/// Request & Response Hooks
+pub mod middleware;
+
+/// Custom Route Requests
+pub mod send;
+
+/// Transactional Batch Requests
+pub mod batch;
+
+/// Filter Expressions
+pub mod filter;
+
+/// Sort & Expand Options
+pub mod query;
+
+/// Keyset Pagination
+pub mod cursor;
+
+/// File Uploads & Downloads
+pub mod files;
+
+/// OAuth2 Sign In For Native Apps
+#[cfg(feature = "oauth2-loopback")]
+pub mod oauth2;
+
 mod httpc;
+mod trace;
+
+pub use reqwest;
@@ -1,6 +1,7 @@
 use crate::auth::AuthenticatedRequest;
-use crate::httpc::HttpClient;
-use anyhow::Result;
+use crate::error::Result;
+use crate::filter::Filter;
+use crate::httpc::{HttpClient, RequestKey};
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -14,16 +15,18 @@ pub struct LogsManager<'a> {
 pub struct LogListRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: &'a str,
+    pub(crate) request_key: RequestKey,
     pub page: i32,
     pub per_page: i32,
     pub sort: Option<&'a str>,
-    pub filter: Option<&'a str>,
+    pub filter: Option<Filter>,
 }
 
 #[derive(Debug, Clone)]
 pub struct LogViewRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: &'a str,
+    pub(crate) request_key: RequestKey,
     pub id: &'a str,
 }
 
@@ -31,7 +34,8 @@ pub struct LogViewRequestBuilder<'a> {
 pub struct LogStatisticsRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: &'a str,
-    pub filter: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
+    pub filter: Option<Filter>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -65,28 +69,33 @@ pub struct LogStatDataPoint {
 }
 
 impl<'a> LogStatisticsRequestBuilder<'a> {
-    pub fn filter(&self, filter_query: &'a str) -> Self {
+    pub fn filter(&self, filter_query: impl Into<Filter>) -> Self {
         Self {
-            filter: Some(filter_query),
+            filter: Some(filter_query.into()),
             ..self.clone()
         }
     }
 
     pub async fn call(&self) -> Result<Vec<LogStatDataPoint>> {
         let mut build_opts = Vec::new();
-        if let Some(filter_opts) = &self.filter {
-            build_opts.push(("filter", filter_opts.to_owned()));
+        let filter_opts = self.filter.as_ref().map(Filter::validate).transpose()?;
+        if let Some(filter_opts) = &filter_opts {
+            build_opts.push(("filter", filter_opts.as_str()));
         }
 
-        match self
-            .client
+        self.client
             .get("/api/logs/requests/stats", Some(build_opts))
+            .route("/api/logs/requests/stats", None)
+            .request_key(&self.request_key)
             .attach_auth_info(self.token)
-            .send()
+            .fetch::<Vec<LogStatDataPoint>>()
             .await
-        {
-            Ok(result) => Ok(result.json::<Vec<LogStatDataPoint>>().await?),
-            Err(e) => Err(e.into()),
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
         }
     }
 }
@@ -94,15 +103,19 @@ impl<'a> LogStatisticsRequestBuilder<'a> {
 impl<'a> LogViewRequestBuilder<'a> {
     pub async fn call(&self) -> Result<LogListItem> {
         let url = format!("/api/logs/requests/{}", self.id);
-        match self
-            .client
+        self.client
             .get(&url, None)
+            .route("/api/logs/requests/{id}", None)
+            .request_key(&self.request_key)
             .attach_auth_info(self.token)
-            .send()
+            .fetch::<LogListItem>()
             .await
-        {
-            Ok(result) => Ok(result.json::<LogListItem>().await?),
-            Err(e) => Err(e.into()),
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
         }
     }
 }
@@ -122,9 +135,9 @@ impl<'a> LogListRequestBuilder<'a> {
         }
     }
 
-    pub fn filter(&self, filter_opts: &'a str) -> Self {
+    pub fn filter(&self, filter_opts: impl Into<Filter>) -> Self {
         LogListRequestBuilder {
-            filter: Some(filter_opts),
+            filter: Some(filter_opts.into()),
             ..self.clone()
         }
     }
@@ -142,23 +155,28 @@ impl<'a> LogListRequestBuilder<'a> {
         if let Some(sort_opts) = &self.sort {
             build_opts.push(("sort", sort_opts.to_owned()))
         }
-        if let Some(filter_opts) = &self.filter {
-            build_opts.push(("filter", filter_opts.to_owned()))
+        let filter_opts = self.filter.as_ref().map(Filter::validate).transpose()?;
+        if let Some(filter_opts) = &filter_opts {
+            build_opts.push(("filter", filter_opts.as_str()))
         }
         let per_page_opts = self.per_page.to_string();
         let page_opts = self.page.to_string();
         build_opts.push(("perPage", per_page_opts.as_str()));
         build_opts.push(("page", page_opts.as_str()));
 
-        match self
-            .client
+        self.client
             .get("/api/logs/requests", Some(build_opts))
+            .route("/api/logs/requests", None)
+            .request_key(&self.request_key)
             .attach_auth_info(self.token)
-            .send()
+            .fetch::<LogList>()
             .await
-        {
-            Ok(result) => Ok(result.json::<LogList>().await?),
-            Err(e) => Err(e.into()),
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
         }
     }
 }
@@ -168,6 +186,7 @@ impl<'a> LogsManager<'a> {
         LogListRequestBuilder {
             client: self.client,
             token: self.token,
+            request_key: RequestKey::Auto,
             page: 1,
             per_page: 100,
             sort: None,
@@ -179,6 +198,7 @@ impl<'a> LogsManager<'a> {
         LogViewRequestBuilder {
             client: self.client,
             token: self.token,
+            request_key: RequestKey::Auto,
             id,
         }
     }
@@ -187,6 +207,7 @@ impl<'a> LogsManager<'a> {
         LogStatisticsRequestBuilder {
             client: self.client,
             token: self.token,
+            request_key: RequestKey::Auto,
             filter: None,
         }
     }
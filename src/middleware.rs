@@ -0,0 +1,78 @@
+use crate::error::Result;
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response, StatusCode};
+use std::fmt;
+use std::sync::Arc;
+
+/// Hooks around every request the client sends, realtime subscriptions
+/// included.
+///
+/// `before_send` runs in registration order right before each attempt goes
+/// out, so it sees retries as separate requests. `after_send` runs in reverse
+/// order on every response received, before it is checked for errors and
+/// decoded. `transform_body` then runs in reverse order on bodies the client
+/// reads in full, decoded records and error responses, and may replace them.
+/// Streamed bodies such as file downloads and realtime events skip it.
+/// Returning an error from any hook aborts the call with that error.
+pub trait Middleware: Send + Sync {
+    fn before_send(&self, _request: &mut Request) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_send(&self, response: Response) -> Result<Response> {
+        Ok(response)
+    }
+
+    fn transform_body(
+        &self,
+        _status: StatusCode,
+        _headers: &HeaderMap,
+        body: Bytes,
+    ) -> Result<Bytes> {
+        Ok(body)
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Middlewares(Vec<Arc<dyn Middleware>>);
+
+impl Middlewares {
+    pub(crate) fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        self.0.push(middleware);
+    }
+
+    pub(crate) fn before_send(&self, request: &mut Request) -> Result<()> {
+        for middleware in &self.0 {
+            middleware.before_send(request)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn after_send(&self, mut response: Response) -> Result<Response> {
+        for middleware in self.0.iter().rev() {
+            response = middleware.after_send(response)?;
+        }
+        Ok(response)
+    }
+
+    pub(crate) fn transform_body(
+        &self,
+        status: StatusCode,
+        headers: &HeaderMap,
+        mut body: Bytes,
+    ) -> Result<Bytes> {
+        for middleware in self.0.iter().rev() {
+            body = middleware.transform_body(status, headers, body)?;
+        }
+        Ok(body)
+    }
+}
+
+impl fmt::Debug for Middlewares {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Middlewares")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
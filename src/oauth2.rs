@@ -0,0 +1,247 @@
+use crate::auth::{Authorized, OAuth2Meta, Unauthorized};
+use crate::client::Client;
+use crate::error::{Error, Result};
+use percent_encoding::percent_decode_str;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const SUCCESS_PAGE: &str = "Signed in, you can close this window.";
+const FAILURE_PAGE: &str = "Sign in failed, you can close this window.";
+
+/// Signs in through the system browser. A listener on `127.0.0.1` receives
+/// the provider redirect, so the app needs no registered custom URL scheme.
+///
+/// The provider must accept `http://127.0.0.1:{port}{callback_path}` as a
+/// redirect URL. With the default port `0` any free port is used.
+#[derive(Clone)]
+pub struct OAuth2LoopbackBuilder<'a> {
+    client: &'a Client<Unauthorized>,
+    pub collection: &'a str,
+    pub provider: &'a str,
+    pub port: u16,
+    pub callback_path: String,
+    pub create_data: Option<Value>,
+    pub timeout: Duration,
+    open_url: Arc<OpenUrlCallback<'a>>,
+}
+
+type OpenUrlCallback<'a> = dyn Fn(&str) + Send + Sync + 'a;
+
+struct Callback {
+    path: String,
+    params: HashMap<String, String>,
+}
+
+impl<'a> OAuth2LoopbackBuilder<'a> {
+    pub fn port(&self, port: u16) -> Self {
+        Self {
+            port,
+            ..self.clone()
+        }
+    }
+
+    pub fn callback_path(&self, callback_path: &str) -> Self {
+        Self {
+            callback_path: callback_path.to_string(),
+            ..self.clone()
+        }
+    }
+
+    pub fn create_data(&self, create_data: Value) -> Self {
+        Self {
+            create_data: Some(create_data),
+            ..self.clone()
+        }
+    }
+
+    /// How long to wait for the browser to come back, 5 minutes by default.
+    pub fn timeout(&self, timeout: Duration) -> Self {
+        Self {
+            timeout,
+            ..self.clone()
+        }
+    }
+
+    pub async fn call(&self) -> Result<(Client<Authorized>, OAuth2Meta)> {
+        let methods = self.client.list_auth_methods(self.collection).await?;
+        let provider = methods
+            .oauth2
+            .providers
+            .into_iter()
+            .find(|provider| provider.name == self.provider)
+            .ok_or_else(|| {
+                Error::OAuth2(format!(
+                    "provider `{}` is not enabled for `{}`",
+                    self.provider, self.collection
+                ))
+            })?;
+
+        let listener = TcpListener::bind(("127.0.0.1", self.port)).await?;
+        let redirect_url = format!(
+            "http://127.0.0.1:{}{}",
+            listener.local_addr()?.port(),
+            self.callback_path
+        );
+        (self.open_url)(&provider.auth_url_with_redirect(&redirect_url));
+
+        let code =
+            tokio::time::timeout(self.timeout, self.receive_code(&listener, &provider.state))
+                .await
+                .map_err(|_| {
+                    Error::OAuth2("timed out waiting for the provider redirect".into())
+                })??;
+
+        self.client
+            .auth_with_oauth2_code(
+                self.collection,
+                &provider.name,
+                &code,
+                &provider.code_verifier,
+                &redirect_url,
+                self.create_data.clone(),
+            )
+            .await
+    }
+
+    /// Waits for the callback. Each connection is answered on its own task,
+    /// so idle ones like speculative preconnects can not hold up the rest.
+    async fn receive_code(&self, listener: &TcpListener, state: &str) -> Result<String> {
+        let (results, mut received) = mpsc::channel(1);
+        let mut connections = JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    connections.spawn(answer(
+                        stream,
+                        self.callback_path.clone(),
+                        state.to_string(),
+                        results.clone(),
+                    ));
+                }
+                Some(result) = received.recv() => return result,
+            }
+        }
+    }
+}
+
+/// Reports the outcome if the connection is the callback, answering anything
+/// else (favicons etc.) with 404.
+async fn answer(
+    mut stream: TcpStream,
+    callback_path: String,
+    state: String,
+    results: mpsc::Sender<Result<String>>,
+) {
+    let callback = match tokio::time::timeout(READ_TIMEOUT, read_callback(&mut stream)).await {
+        Ok(Ok(callback)) => callback,
+        _ => return,
+    };
+    if callback.path != callback_path {
+        respond(&mut stream, "404 Not Found", "").await;
+        return;
+    }
+
+    let result = code_from_params(&callback.params, &state);
+    let page = match result {
+        Ok(_) => SUCCESS_PAGE,
+        Err(_) => FAILURE_PAGE,
+    };
+    respond(&mut stream, "200 OK", page).await;
+    let _ = results.send(result).await;
+}
+
+fn code_from_params(params: &HashMap<String, String>, state: &str) -> Result<String> {
+    if let Some(error) = params.get("error") {
+        return Err(Error::OAuth2(match params.get("error_description") {
+            Some(description) => format!("{}: {}", error, description),
+            None => error.clone(),
+        }));
+    }
+    if params.get("state").map(String::as_str) != Some(state) {
+        return Err(Error::OAuth2(
+            "state of the provider redirect does not match".into(),
+        ));
+    }
+    params
+        .get("code")
+        .cloned()
+        .ok_or_else(|| Error::OAuth2("provider redirect is missing the code".into()))
+}
+
+async fn read_callback(stream: &mut TcpStream) -> Result<Callback> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    // Drain the headers, closing with unread data resets the connection.
+    let mut header = String::new();
+    while reader.read_line(&mut header).await? > 2 {
+        header.clear();
+    }
+
+    let target = request_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| Error::OAuth2("malformed callback request".into()))?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (decode(key), decode(value)))
+        .collect();
+
+    Ok(Callback {
+        path: path.to_string(),
+        params,
+    })
+}
+
+fn decode(value: &str) -> String {
+    percent_decode_str(&value.replace('+', " "))
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\ncontent-type: text/plain; charset=utf-8\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+impl Client<Unauthorized> {
+    /// Starts a browser based OAuth2 sign in, see [`OAuth2LoopbackBuilder`].
+    /// `open_url` receives the provider URL and should open it in a browser.
+    pub fn oauth2_loopback<'a, F>(
+        &'a self,
+        collection: &'a str,
+        provider: &'a str,
+        open_url: F,
+    ) -> OAuth2LoopbackBuilder<'a>
+    where
+        F: Fn(&str) + Send + Sync + 'a,
+    {
+        OAuth2LoopbackBuilder {
+            client: self,
+            collection,
+            provider,
+            port: 0,
+            callback_path: "/callback".to_string(),
+            create_data: None,
+            timeout: Duration::from_secs(300),
+            open_url: Arc::new(open_url),
+        }
+    }
+}
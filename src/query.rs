@@ -0,0 +1,189 @@
+use crate::error::{Error, Result};
+use std::fmt;
+
+/// PocketBase stops expanding relations deeper than this.
+const MAX_EXPAND_DEPTH: usize = 6;
+
+/// Sort order of a list request, e.g. `Sort::new().desc("created").asc("title")`.
+///
+/// Plain strings such as `"-created,+title"` convert into a `Sort` too and
+/// are checked the same way before the request is sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sort {
+    fields: Vec<(String, bool)>,
+}
+
+/// Relations to expand, e.g.
+/// `Expand::new().relation("author.profile").back_relation("comments", "post")`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expand {
+    paths: Vec<String>,
+}
+
+impl Sort {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Random order. It can not be combined with other fields.
+    pub fn random() -> Self {
+        Self::new().asc("@random")
+    }
+
+    /// Insertion order, oldest first. `@rowid` is unique, so it can only be
+    /// the last field, e.g. as a tiebreaker after a non unique one.
+    pub fn rowid(self) -> Self {
+        self.asc("@rowid")
+    }
+
+    /// Insertion order, newest first.
+    pub fn rowid_desc(self) -> Self {
+        self.desc("@rowid")
+    }
+
+    pub fn asc(mut self, field: &str) -> Self {
+        self.fields.push((field.trim().to_string(), false));
+        self
+    }
+
+    pub fn desc(mut self, field: &str) -> Self {
+        self.fields.push((field.trim().to_string(), true));
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<String> {
+        let invalid = |reason: String| Err(Error::Query(format!("invalid sort: {}", reason)));
+
+        if self.fields.is_empty() {
+            return invalid("no fields".to_string());
+        }
+        for (index, (field, desc)) in self.fields.iter().enumerate() {
+            if !is_identifier(field) {
+                return invalid(format!("`{}` is not a field", field));
+            }
+            if self.fields[..index].iter().any(|(other, _)| other == field) {
+                return invalid(format!("`{}` is listed twice", field));
+            }
+            if field == "@random" && (*desc || self.fields.len() > 1) {
+                return invalid("`@random` must be the only, ascending, field".to_string());
+            }
+            if field == "@rowid" && index + 1 < self.fields.len() {
+                return invalid("`@rowid` is unique and must be the last field".to_string());
+            }
+        }
+
+        Ok(self.to_string())
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (field, desc)) in self.fields.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            if *desc {
+                f.write_str("-")?;
+            }
+            f.write_str(field)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Sort {
+    fn from(sort: &str) -> Self {
+        sort.split(',').fold(Sort::new(), |sort, field| {
+            let field = field.trim();
+            match field.strip_prefix('-') {
+                Some(field) => sort.desc(field),
+                None => sort.asc(field.strip_prefix('+').unwrap_or(field)),
+            }
+        })
+    }
+}
+
+impl From<String> for Sort {
+    fn from(sort: String) -> Self {
+        Sort::from(sort.as_str())
+    }
+}
+
+impl Expand {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Relation field, or a dotted path of nested relations such as
+    /// `author.profile`.
+    pub fn relation(mut self, path: &str) -> Self {
+        self.paths.push(path.trim().to_string());
+        self
+    }
+
+    /// Records of `collection` pointing back through their `field`, i.e.
+    /// `{collection}_via_{field}`.
+    pub fn back_relation(self, collection: &str, field: &str) -> Self {
+        self.relation(&format!("{}_via_{}", collection, field))
+    }
+
+    /// Expands `expand` on the records of the `parent` relation.
+    pub fn nested(mut self, parent: &str, expand: Expand) -> Self {
+        for path in expand.paths {
+            self.paths.push(format!("{}.{}", parent.trim(), path));
+        }
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<String> {
+        let invalid = |reason: String| Err(Error::Query(format!("invalid expand: {}", reason)));
+
+        if self.paths.is_empty() {
+            return invalid("no relations".to_string());
+        }
+        for (index, path) in self.paths.iter().enumerate() {
+            let segments: Vec<&str> = path.split('.').collect();
+            if let Some(segment) = segments.iter().find(|segment| !is_identifier(segment)) {
+                return invalid(format!("`{}` is not a relation in `{}`", segment, path));
+            }
+            if segments.len() > MAX_EXPAND_DEPTH {
+                return invalid(format!(
+                    "`{}` is nested deeper than {} levels",
+                    path, MAX_EXPAND_DEPTH
+                ));
+            }
+            if self.paths[..index].contains(path) {
+                return invalid(format!("`{}` is listed twice", path));
+            }
+        }
+
+        Ok(self.to_string())
+    }
+}
+
+impl fmt::Display for Expand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.paths.join(","))
+    }
+}
+
+impl From<&str> for Expand {
+    fn from(expand: &str) -> Self {
+        expand
+            .split(',')
+            .fold(Expand::new(), |expand, path| expand.relation(path))
+    }
+}
+
+impl From<String> for Expand {
+    fn from(expand: String) -> Self {
+        Expand::from(expand.as_str())
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '@' | ':'))
+}
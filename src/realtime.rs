@@ -1,5 +1,6 @@
-use crate::httpc::HttpClient;
-use anyhow::{anyhow, Result};
+use crate::error::{Error, Result};
+use crate::httpc::{HttpClient, RequestKey};
+use crate::trace;
 use eventsource_stream::Eventsource;
 use futures_lite::stream::StreamExt;
 use serde::Deserialize;
@@ -65,22 +66,31 @@ impl RealtimeClient {
     }
 
     async fn connect(&self) -> Result<()> {
+        let span = trace::realtime_connect();
+        trace::instrument(&span, self.open_stream()).await
+    }
+
+    async fn open_stream(&self) -> Result<()> {
         let response = self
             .inner
             .http_client
             .get("/api/realtime", None)
+            .route("/api/realtime", None)
+            .request_key(&RequestKey::Disabled)
+            .streaming()
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         let mut stream = Box::pin(response.bytes_stream().eventsource());
 
         let first_event = stream
             .next()
             .await
-            .ok_or_else(|| anyhow!("Stream closed immediately"))??;
+            .ok_or_else(|| Error::Realtime("Stream closed immediately".to_string()))?
+            .map_err(|e| Error::Realtime(e.to_string()))?;
 
         if first_event.event == "PB_CONNECT" {
+            trace::realtime_connected();
             *self.inner.client_id.write().unwrap() = Some(first_event.id);
             *self.inner.connected.write().unwrap() = true;
 
@@ -88,7 +98,10 @@ impl RealtimeClient {
                 self.submit_subscriptions().await?;
             }
         } else {
-            return Err(anyhow!("Expected PB_CONNECT, got {}", first_event.event));
+            return Err(Error::Realtime(format!(
+                "Expected PB_CONNECT, got {}",
+                first_event.event
+            )));
         }
 
         let inner = self.inner.clone();
@@ -100,13 +113,16 @@ impl RealtimeClient {
                         if let Some(callbacks) =
                             inner.subscriptions.read().unwrap().get(&event.event)
                         {
-                            let data = serde_json::from_str::<EventResponse>(&event.data);
-
-                            if let Ok(data) = data {
-                                for cb in callbacks {
-                                    cb(data.clone());
+                            let span = trace::realtime_dispatch(&event.event, callbacks.len());
+                            trace::in_span(&span, || {
+                                let data = serde_json::from_str::<EventResponse>(&event.data);
+
+                                if let Ok(data) = data {
+                                    for cb in callbacks {
+                                        cb(data.clone());
+                                    }
                                 }
-                            }
+                            });
                         }
                     }
                     Err(_) => break,
@@ -158,7 +174,9 @@ impl RealtimeClient {
     }
 
     async fn submit_subscriptions(&self) -> Result<()> {
-        let client_id = self.client_id().ok_or_else(|| anyhow!("Not connected"))?;
+        let client_id = self
+            .client_id()
+            .ok_or_else(|| Error::Realtime("Not connected".to_string()))?;
         let subs: Vec<String> = self
             .inner
             .subscriptions
@@ -168,18 +186,19 @@ impl RealtimeClient {
             .cloned()
             .collect();
 
+        let span = trace::realtime_submit(subs.len());
+
         let body = serde_json::json!({
             "clientId": client_id,
             "subscriptions": subs
         });
 
-        let _res = self
+        let request = self
             .inner
             .http_client
             .post("/api/realtime", body.to_string())
-            .send()
-            .await?
-            .error_for_status()?;
+            .route("/api/realtime", None);
+        trace::instrument(&span, request.send()).await?;
         Ok(())
     }
 
@@ -1,9 +1,21 @@
 use crate::auth::AuthenticatedRequest;
-use crate::httpc::HttpClient;
+use crate::batch::BatchRequestBuilder;
+use crate::error::{ApiError, Error, Result};
+use crate::files::{FileFields, FileOwner, FileUpload};
+use crate::filter::Filter;
+use crate::httpc::{HttpClient, RequestKey};
+use crate::query::{Expand, Sort};
 use crate::realtime::{EventResponse, RealtimeClient};
-use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use futures_util::future::{self, Either};
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+use reqwest::Method;
 use serde::Serialize;
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio_util::task::AbortOnDropHandle;
 
 pub type RecordId = String;
 
@@ -17,6 +29,116 @@ pub struct RecordBaseFields {
     pub collection_id: String,
 }
 
+/// A record with its system fields, the collection specific fields being
+/// flattened into `data`:
+///
+/// ```
+/// # use pocketbase_sdk::records::Record;
+/// #[derive(Default, serde::Deserialize)]
+/// struct Post {
+///     title: String,
+/// }
+///
+/// let post: Record<Post> = serde_json::from_str(r#"{
+///     "id": "p1",
+///     "collectionId": "c1",
+///     "collectionName": "posts",
+///     "created": "2024-01-02 03:04:05.678Z",
+///     "updated": "2024-01-02 03:04:05.678Z",
+///     "title": "Hello"
+/// }"#).unwrap();
+/// assert_eq!(post.data.title, "Hello");
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record<T> {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: RecordId,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub collection_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub collection_name: String,
+    #[serde(default, with = "datetime")]
+    pub created: DateTime<Utc>,
+    #[serde(default, with = "datetime")]
+    pub updated: DateTime<Utc>,
+    /// Expanded relations keyed by field name, see [`Record::expanded`].
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub expand: HashMap<String, Value>,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+impl<T> Record<T> {
+    /// Decodes the expanded `relation`, a single record or a list of records
+    /// depending on the relation field. `None` if it was not expanded.
+    pub fn expanded<E: DeserializeOwned>(&self, relation: &str) -> Result<Option<E>> {
+        self.expand
+            .get(relation)
+            .map(|value| serde_json::from_value(value.clone()).map_err(Error::Decode))
+            .transpose()
+    }
+}
+
+impl<T> From<&Record<T>> for RecordBaseFields {
+    fn from(record: &Record<T>) -> Self {
+        RecordBaseFields {
+            id: record.id.clone(),
+            collection_name: record.collection_name.clone(),
+            collection_id: record.collection_id.clone(),
+        }
+    }
+}
+
+impl FileOwner for RecordBaseFields {
+    fn record_id(&self) -> &str {
+        &self.id
+    }
+
+    fn collection(&self) -> &str {
+        collection_id_or_name(&self.collection_id, &self.collection_name)
+    }
+}
+
+impl<T> FileOwner for Record<T> {
+    fn record_id(&self) -> &str {
+        &self.id
+    }
+
+    fn collection(&self) -> &str {
+        collection_id_or_name(&self.collection_id, &self.collection_name)
+    }
+}
+
+fn collection_id_or_name<'a>(id: &'a str, name: &'a str) -> &'a str {
+    if id.is_empty() { name } else { id }
+}
+
+/// PocketBase datetimes, such as `2024-01-02 03:04:05.678Z`. Empty strings
+/// are read as the default datetime.
+mod datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&value.format("%Y-%m-%d %H:%M:%S%.3fZ"))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        if value.is_empty() {
+            return Ok(DateTime::default());
+        }
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RecordsManager<'a> {
     pub(crate) http_client: &'a HttpClient,
@@ -29,13 +151,16 @@ pub struct RecordsManager<'a> {
 pub struct RecordsListRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
     pub collection_name: &'a str,
-    pub filter: Option<String>,
-    pub sort: Option<String>,
-    pub expand: Option<String>,
+    pub filter: Option<Filter>,
+    pub sort: Option<Sort>,
+    pub expand: Option<Expand>,
     pub fields: Option<String>,
     pub page: i32,
     pub per_page: i32,
+    pub skip_total: bool,
+    pub concurrency: usize,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -47,57 +172,181 @@ pub struct RecordList<T> {
     pub items: Vec<T>,
 }
 
-impl<'a> RecordsListRequestBuilder<'a> {
-    pub async fn call<T: Default + DeserializeOwned>(&self) -> Result<RecordList<T>> {
+impl<T> RecordList<T> {
+    /// Whether no page follows this one. Relies on the page size reported by
+    /// the server, which may cap `perPage`, and works with `skipTotal`.
+    pub(crate) fn is_last_page(&self) -> bool {
+        self.items.is_empty()
+            || self.items.len() < self.per_page as usize
+            || (self.total_items >= 0
+                && self.page as i64 * self.per_page as i64 >= self.total_items as i64)
+    }
+}
+
+/// Owned copy of a list request, so pages can be fetched from a spawned task.
+#[derive(Debug, Clone)]
+struct PageRequest {
+    client: HttpClient,
+    token: Option<String>,
+    collection_name: String,
+    query: Vec<(&'static str, String)>,
+}
+
+impl PageRequest {
+    async fn fetch<T: DeserializeOwned>(self, page: i32) -> Result<RecordList<T>> {
         let url = format!("/api/collections/{}/records", self.collection_name);
+        let page = page.to_string();
+        let mut build_opts: Vec<(&str, &str)> = self
+            .query
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+        build_opts.push(("page", &page));
 
-        let mut build_opts: Vec<(&str, &str)> = vec![];
+        self.client
+            .get(&url, Some(build_opts))
+            .route(
+                "/api/collections/{collection}/records",
+                Some(&self.collection_name),
+            )
+            .request_key(&RequestKey::Disabled)
+            .try_attach_auth_info(self.token.as_deref())
+            .fetch::<RecordList<T>>()
+            .await
+    }
+
+    fn spawn<T>(&self, page: i32) -> AbortOnDropHandle<Result<RecordList<T>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        AbortOnDropHandle::new(tokio::spawn(self.clone().fetch(page)))
+    }
+}
+
+struct PageStream<T> {
+    request: PageRequest,
+    /// Prefetched `page`, `None` until the stream is first polled.
+    next: Option<AbortOnDropHandle<Result<RecordList<T>>>>,
+    page: i32,
+}
+
+impl<'a> RecordsListRequestBuilder<'a> {
+    fn query(&self) -> Result<Vec<(&'static str, String)>> {
+        let mut build_opts = vec![];
         if let Some(filter_opts) = &self.filter {
-            build_opts.push(("filter", filter_opts))
+            build_opts.push(("filter", filter_opts.validate()?))
         }
         if let Some(sort_opts) = &self.sort {
-            build_opts.push(("sort", sort_opts))
+            build_opts.push(("sort", sort_opts.validate()?))
         }
         if let Some(expand_opts) = &self.expand {
-            build_opts.push(("expand", expand_opts))
+            build_opts.push(("expand", expand_opts.validate()?))
         }
         if let Some(fields_opts) = &self.fields {
-            build_opts.push(("fields", fields_opts))
+            build_opts.push(("fields", fields_opts.clone()))
+        }
+        if self.skip_total {
+            build_opts.push(("skipTotal", "1".to_string()))
         }
-        let per_page_opts = self.per_page.to_string();
+        build_opts.push(("perPage", self.per_page.to_string()));
+
+        Ok(build_opts)
+    }
+
+    pub async fn call<T: Default + DeserializeOwned>(&self) -> Result<RecordList<T>> {
+        let url = format!("/api/collections/{}/records", self.collection_name);
+
+        let query = self.query()?;
         let page_opts = self.page.to_string();
-        build_opts.push(("perPage", per_page_opts.as_str()));
+        let mut build_opts: Vec<(&str, &str)> = query
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
         build_opts.push(("page", page_opts.as_str()));
 
-        match self
-            .client
+        self.client
             .get(&url, Some(build_opts))
+            .route(
+                "/api/collections/{collection}/records",
+                Some(self.collection_name),
+            )
+            .request_key(&self.request_key)
             .try_attach_auth_info(self.token)
-            .send()
+            .fetch::<RecordList<T>>()
             .await
-        {
-            Ok(result) => Ok(result.json::<RecordList<T>>().await?),
-            Err(e) => Err(e.into()),
-        }
     }
 
-    pub fn filter(&self, filter_opts: &str) -> Self {
+    /// Pages of the list from `page` on, fetched lazily. The next page is
+    /// requested in the background while the current one is processed.
+    /// Pages are `per_page` records at most, the server may cap it further.
+    pub fn into_page_stream<T>(self) -> impl Stream<Item = Result<RecordList<T>>> + Send + 'static
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let request = match self.query() {
+            Ok(query) => PageRequest {
+                client: self.client.clone(),
+                token: self.token.map(str::to_string),
+                collection_name: self.collection_name.to_string(),
+                query,
+            },
+            Err(e) => return Either::Left(stream::once(future::ready(Err(e)))),
+        };
+        let first = PageStream {
+            next: None,
+            request,
+            page: self.page,
+        };
+
+        Either::Right(stream::unfold(Some(first), |state| async move {
+            let state = state?;
+            let next = match state.next {
+                Some(next) => next,
+                None => state.request.spawn(state.page),
+            };
+            let list = match next.await {
+                Ok(Ok(list)) => list,
+                Ok(Err(e)) => return Some((Err(e), None)),
+                Err(e) if e.is_cancelled() => return Some((Err(Error::Cancelled), None)),
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            };
+            let next = (!list.is_last_page()).then(|| PageStream {
+                next: Some(state.request.spawn(state.page + 1)),
+                request: state.request,
+                page: state.page + 1,
+            });
+
+            Some((Ok(list), next))
+        }))
+    }
+
+    /// Records of the list, see [`Self::into_page_stream`].
+    pub fn into_stream<T>(self) -> impl Stream<Item = Result<T>> + Send + 'static
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        self.into_page_stream::<T>()
+            .map_ok(|list| stream::iter(list.items.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    pub fn filter(&self, filter_opts: impl Into<Filter>) -> Self {
         Self {
-            filter: Some(filter_opts.to_string()),
+            filter: Some(filter_opts.into()),
             ..self.clone()
         }
     }
 
-    pub fn sort(&self, sort_opts: &str) -> Self {
+    pub fn sort(&self, sort_opts: impl Into<Sort>) -> Self {
         Self {
-            sort: Some(sort_opts.to_string()),
+            sort: Some(sort_opts.into()),
             ..self.clone()
         }
     }
 
-    pub fn expand(&self, expand_opts: &str) -> Self {
+    pub fn expand(&self, expand_opts: impl Into<Expand>) -> Self {
         Self {
-            expand: Some(expand_opts.to_string()),
+            expand: Some(expand_opts.into()),
             ..self.clone()
         }
     }
@@ -123,18 +372,39 @@ impl<'a> RecordsListRequestBuilder<'a> {
         }
     }
 
+    /// Sends `skipTotal=1` so PocketBase skips counting the matching records.
+    /// `total_items` of the returned lists is then `-1`.
+    pub fn skip_total(&self, skip_total: bool) -> Self {
+        Self {
+            skip_total,
+            ..self.clone()
+        }
+    }
+
+    /// Number of pages `full_list` fetches at once. Above 1, the first page
+    /// is read for `totalItems` and the remaining pages are requested
+    /// concurrently. Not applied together with `skip_total`.
+    pub fn concurrency(&self, concurrency: usize) -> Self {
+        Self {
+            concurrency,
+            ..self.clone()
+        }
+    }
+
     pub async fn full_list<T: Default + DeserializeOwned>(&self) -> Result<Vec<T>> {
+        if self.concurrency > 1 && !self.skip_total {
+            return self.concurrent_full_list().await;
+        }
+
         let mut result: Vec<T> = vec![];
         let mut page = 1;
-        let per_page = self.per_page;
 
         loop {
             let list = self.page(page).call::<T>().await?;
-            let items_len = list.items.len();
-            let total_items = list.total_items as usize;
+            let is_last_page = list.is_last_page();
             result.extend(list.items);
 
-            if items_len < per_page as usize || result.len() >= total_items {
+            if is_last_page {
                 break;
             }
             page += 1;
@@ -142,15 +412,138 @@ impl<'a> RecordsListRequestBuilder<'a> {
 
         Ok(result)
     }
+
+    /// Pages are fetched without request keys, so they do not cancel each
+    /// other. Records added during the scan can push others onto later pages,
+    /// so the scan continues past the planned pages while they come back full
+    /// and records seen twice are dropped.
+    async fn concurrent_full_list<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        let pages = &Self {
+            request_key: RequestKey::Disabled,
+            ..self.clone()
+        };
+
+        let first = pages.page(1).call::<Value>().await?;
+        let per_page = first.per_page.max(1) as i64;
+        let page_count = (first.total_items as i64 + per_page - 1) / per_page;
+        let mut lists = vec![first];
+
+        if !lists[0].is_last_page() {
+            let rest: Vec<RecordList<Value>> = stream::iter(2..=page_count as i32)
+                .map(|page| async move { pages.page(page).call::<Value>().await })
+                .buffered(self.concurrency)
+                .try_collect()
+                .await?;
+            lists.extend(rest);
+
+            while let Some(last) = lists.last().filter(|list| !list.is_last_page()) {
+                let next = pages.page(last.page + 1).call::<Value>().await?;
+                lists.push(next);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        lists
+            .into_iter()
+            .flat_map(|list| list.items)
+            .filter(|item| match item.get("id").and_then(Value::as_str) {
+                Some(id) => seen.insert(id.to_string()),
+                None => true,
+            })
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<T>, _>>()
+            .map_err(Error::Decode)
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
+        }
+    }
+}
+
+/// First record matching a filter, failing with a 404 [`Error::Api`] if
+/// there is none.
+#[derive(Debug, Clone)]
+pub struct RecordFirstListItemRequestBuilder<'a> {
+    pub(crate) list: RecordsListRequestBuilder<'a>,
+}
+
+/// Number of records matching a filter, read from `totalItems`.
+#[derive(Debug, Clone)]
+pub struct RecordCountRequestBuilder<'a> {
+    pub(crate) list: RecordsListRequestBuilder<'a>,
+}
+
+impl<'a> RecordFirstListItemRequestBuilder<'a> {
+    pub async fn call<T: Default + DeserializeOwned>(&self) -> Result<T> {
+        let list = self.list.call::<T>().await?;
+
+        list.items.into_iter().next().ok_or_else(|| {
+            Error::Api(ApiError {
+                status: 404,
+                message: "The requested resource wasn't found.".to_string(),
+                data: Default::default(),
+            })
+        })
+    }
+
+    pub fn expand(&self, expand_opts: impl Into<Expand>) -> Self {
+        Self {
+            list: self.list.expand(expand_opts),
+        }
+    }
+
+    pub fn fields(&self, fields_opts: &str) -> Self {
+        Self {
+            list: self.list.fields(fields_opts),
+        }
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            list: self.list.request_key(key),
+        }
+    }
+}
+
+impl<'a> RecordCountRequestBuilder<'a> {
+    pub async fn call(&self) -> Result<u64> {
+        let list = self.list.call::<Value>().await?;
+
+        Ok(list.total_items.max(0) as u64)
+    }
+
+    pub fn expand(&self, expand_opts: impl Into<Expand>) -> Self {
+        Self {
+            list: self.list.expand(expand_opts),
+        }
+    }
+
+    /// Fields of the single record fetched along with the count, `id` by
+    /// default.
+    pub fn fields(&self, fields_opts: &str) -> Self {
+        Self {
+            list: self.list.fields(fields_opts),
+        }
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            list: self.list.request_key(key),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RecordViewRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
     pub collection_name: &'a str,
     pub identifier: &'a str,
-    pub expand: Option<String>,
+    pub expand: Option<Expand>,
     pub fields: Option<String>,
 }
 
@@ -162,28 +555,29 @@ impl<'a> RecordViewRequestBuilder<'a> {
         );
 
         let mut build_opts: Vec<(&str, &str)> = vec![];
-        if let Some(expand_opts) = &self.expand {
+        let expand_opts = self.expand.as_ref().map(Expand::validate).transpose()?;
+        if let Some(expand_opts) = &expand_opts {
             build_opts.push(("expand", expand_opts))
         }
         if let Some(fields_opts) = &self.fields {
             build_opts.push(("fields", fields_opts))
         }
 
-        match self
-            .client
+        self.client
             .get(&url, Some(build_opts))
+            .route(
+                "/api/collections/{collection}/records/{id}",
+                Some(self.collection_name),
+            )
+            .request_key(&self.request_key)
             .try_attach_auth_info(self.token)
-            .send()
+            .fetch::<T>()
             .await
-        {
-            Ok(result) => Ok(result.json::<T>().await?),
-            Err(e) => Err(anyhow!("error: {}", e)),
-        }
     }
 
-    pub fn expand(&self, expand_opts: &str) -> Self {
+    pub fn expand(&self, expand_opts: impl Into<Expand>) -> Self {
         Self {
-            expand: Some(expand_opts.to_string()),
+            expand: Some(expand_opts.into()),
             ..self.clone()
         }
     }
@@ -194,12 +588,20 @@ impl<'a> RecordViewRequestBuilder<'a> {
             ..self.clone()
         }
     }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct RecordDeleteRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
     pub collection_name: &'a str,
     pub identifier: &'a str,
 }
@@ -210,39 +612,214 @@ impl<'a> RecordDeleteRequestBuilder<'a> {
             "/api/collections/{}/records/{}",
             self.collection_name, self.identifier
         );
-        match self
-            .client
+        self.client
             .delete(&url)
+            .route(
+                "/api/collections/{collection}/records/{id}",
+                Some(self.collection_name),
+            )
+            .request_key(&self.request_key)
             .try_attach_auth_info(self.token)
             .send()
-            .await
-        {
-            Ok(result) => {
-                if result.status() == 204 {
-                    Ok(())
-                } else {
-                    Err(anyhow!("Failed to delete"))
-                }
-            }
-            Err(e) => Err(anyhow!("error: {}", e)),
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct RecordDeleteAllRequestBuilder<'a> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: Option<&'a str>,
     pub collection_name: &'a str,
-    pub filter: Option<&'a str>,
+    pub filter: Option<Filter>,
+    pub concurrency: usize,
+    pub batch_size: Option<usize>,
+    pub(crate) on_progress: Option<Arc<ProgressCallback<'a>>>,
+}
+
+type ProgressCallback<'a> = dyn Fn(DeleteAllProgress) + Send + Sync + 'a;
+
+/// Running totals reported while [`RecordDeleteAllRequestBuilder::call`] works
+/// through the matching records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteAllProgress {
+    pub total: usize,
+    pub deleted: usize,
+    pub failed: usize,
+}
+
+/// Outcome of a bulk delete. Failed records are left in place.
+#[derive(Debug, Default)]
+pub struct DeleteAllSummary {
+    pub deleted: Vec<RecordId>,
+    pub failed: Vec<(RecordId, Error)>,
+}
+
+#[derive(Default, Deserialize)]
+struct RecordIdOnly {
+    id: RecordId,
+}
+
+impl std::fmt::Debug for RecordDeleteAllRequestBuilder<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordDeleteAllRequestBuilder")
+            .field("collection_name", &self.collection_name)
+            .field("filter", &self.filter)
+            .field("concurrency", &self.concurrency)
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> RecordDeleteAllRequestBuilder<'a> {
+    /// Deletes every record matching the filter. Records are removed through
+    /// `/api/batch` transactions and the builder falls back to individual
+    /// deletes when batch requests are disabled on the server, or to find out
+    /// which records of a rejected batch failed.
+    pub async fn call(&self) -> Result<DeleteAllSummary> {
+        let ids = self.matching_ids().await?;
+        let total = ids.len();
+        let mut summary = DeleteAllSummary::default();
+        let mut use_batch = self.batch_size.is_some();
+        let chunk_size = self.batch_size.unwrap_or(total).max(1);
+
+        for chunk in ids.chunks(chunk_size) {
+            if use_batch {
+                match self.delete_batch(chunk).await {
+                    Ok(()) => {
+                        summary.deleted.extend_from_slice(chunk);
+                        self.report(&summary, total);
+                        continue;
+                    }
+                    Err(e) if matches!(e.status(), Some(403 | 404)) => use_batch = false,
+                    Err(_) => {}
+                }
+            }
+            self.delete_each(chunk, &mut summary, total).await;
+        }
+
+        Ok(summary)
+    }
+
+    async fn matching_ids(&self) -> Result<Vec<RecordId>> {
+        let list = RecordsListRequestBuilder {
+            client: self.client,
+            token: self.token,
+            request_key: RequestKey::Disabled,
+            collection_name: self.collection_name,
+            filter: self.filter.clone(),
+            sort: Some(Sort::new().asc("id")),
+            expand: None,
+            fields: Some("id".to_string()),
+            page: 1,
+            per_page: 500,
+            skip_total: true,
+            concurrency: 1,
+        };
+
+        Ok(list
+            .full_list::<RecordIdOnly>()
+            .await?
+            .into_iter()
+            .map(|record| record.id)
+            .collect())
+    }
+
+    async fn delete_batch(&self, ids: &[RecordId]) -> Result<()> {
+        let mut batch = BatchRequestBuilder::new(self.client, self.token).request_key(None);
+        let mut collection = batch.collection(self.collection_name);
+        for id in ids {
+            collection.delete(id);
+        }
+        batch.send().await?;
+        Ok(())
+    }
+
+    async fn delete_each(&self, ids: &[RecordId], summary: &mut DeleteAllSummary, total: usize) {
+        let mut results = stream::iter(ids)
+            .map(|id| async move {
+                let result = RecordDeleteRequestBuilder {
+                    client: self.client,
+                    token: self.token,
+                    request_key: RequestKey::Disabled,
+                    collection_name: self.collection_name,
+                    identifier: id,
+                }
+                .call()
+                .await;
+                (id, result)
+            })
+            .buffer_unordered(self.concurrency.max(1));
+
+        while let Some((id, result)) = results.next().await {
+            match result {
+                Ok(()) => summary.deleted.push(id.clone()),
+                Err(e) => summary.failed.push((id.clone(), e)),
+            }
+            self.report(summary, total);
+        }
+    }
+
+    fn report(&self, summary: &DeleteAllSummary, total: usize) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(DeleteAllProgress {
+                total,
+                deleted: summary.deleted.len(),
+                failed: summary.failed.len(),
+            });
+        }
+    }
+
+    pub fn filter(&self, filter_opts: impl Into<Filter>) -> Self {
+        Self {
+            filter: Some(filter_opts.into()),
+            ..self.clone()
+        }
+    }
+
+    pub fn concurrency(&self, concurrency: usize) -> Self {
+        Self {
+            concurrency,
+            ..self.clone()
+        }
+    }
+
+    /// Number of deletes per `/api/batch` transaction, `None` to always delete
+    /// records one by one.
+    pub fn batch_size(&self, batch_size: Option<usize>) -> Self {
+        Self {
+            batch_size,
+            ..self.clone()
+        }
+    }
+
+    pub fn on_progress<F>(&self, on_progress: F) -> Self
+    where
+        F: Fn(DeleteAllProgress) + Send + Sync + 'a,
+    {
+        Self {
+            on_progress: Some(Arc::new(on_progress)),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RecordCreateRequestBuilder<'a, T: Serialize + Clone> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
     pub collection_name: &'a str,
     pub record: T,
+    pub(crate) files: FileFields,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -258,27 +835,70 @@ pub struct CreateResponse {
 
 impl<'a, T: Serialize + Clone> RecordCreateRequestBuilder<'a, T> {
     pub async fn call(&self) -> Result<CreateResponse> {
+        self.call_as::<CreateResponse>().await
+    }
+
+    /// Decodes the created record as `R`, e.g. a [`Record`].
+    pub async fn call_as<R: DeserializeOwned>(&self) -> Result<R> {
         let url = format!("/api/collections/{}/records", self.collection_name);
-        let payload = serde_json::to_string(&self.record).map_err(anyhow::Error::from)?;
-        match self
-            .client
-            .post(&url, payload)
+        self.files
+            .request(self.client, Method::POST, &url, &self.record)
+            .await?
+            .route(
+                "/api/collections/{collection}/records",
+                Some(self.collection_name),
+            )
+            .request_key(&self.request_key)
             .try_attach_auth_info(self.token)
-            .send()
+            .fetch::<R>()
             .await
-        {
-            Ok(result) => Ok(result.json::<CreateResponse>().await?),
-            Err(e) => Err(anyhow!("error: {}", e)),
+    }
+
+    pub fn file(&self, field: &str, file: FileUpload) -> Self {
+        let mut files = self.files.clone();
+        files.set(field, file);
+        Self {
+            files,
+            ..self.clone()
+        }
+    }
+
+    /// Adds `file` to a multi-file field, keeping the files already stored.
+    pub fn append_file(&self, field: &str, file: FileUpload) -> Self {
+        let mut files = self.files.clone();
+        files.append(field, file);
+        Self {
+            files,
+            ..self.clone()
+        }
+    }
+
+    pub fn remove_file(&self, field: &str, file_name: &str) -> Self {
+        let mut files = self.files.clone();
+        files.remove(field, file_name);
+        Self {
+            files,
+            ..self.clone()
+        }
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
         }
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct RecordUpdateRequestBuilder<'a, K: Serialize + Clone> {
     pub(crate) client: &'a HttpClient,
     pub(crate) token: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
     pub collection_name: &'a str,
     pub id: &'a str,
     pub data: K,
+    pub(crate) files: FileFields,
 }
 
 impl<'a, K: Serialize + Clone> RecordUpdateRequestBuilder<'a, K> {
@@ -287,17 +907,17 @@ impl<'a, K: Serialize + Clone> RecordUpdateRequestBuilder<'a, K> {
             "/api/collections/{}/records/{}",
             self.collection_name, self.id
         );
-        let payload = serde_json::to_string(&self.data).map_err(anyhow::Error::from)?;
-        match self
-            .client
-            .patch(&url, payload)
+        self.files
+            .request(self.client, Method::PATCH, &url, &self.data)
+            .await?
+            .route(
+                "/api/collections/{collection}/records/{id}",
+                Some(self.collection_name),
+            )
+            .request_key(&self.request_key)
             .try_attach_auth_info(self.token)
-            .send()
+            .fetch::<T>()
             .await
-        {
-            Ok(result) => Ok(result.json::<T>().await?),
-            Err(e) => Err(anyhow!("error: {}", e)),
-        }
     }
 
     pub async fn send(&self) -> Result<()> {
@@ -305,16 +925,53 @@ impl<'a, K: Serialize + Clone> RecordUpdateRequestBuilder<'a, K> {
             "/api/collections/{}/records/{}",
             self.collection_name, self.id
         );
-        let payload = serde_json::to_string(&self.data).map_err(anyhow::Error::from)?;
-        match self
-            .client
-            .patch(&url, payload)
+        self.files
+            .request(self.client, Method::PATCH, &url, &self.data)
+            .await?
+            .route(
+                "/api/collections/{collection}/records/{id}",
+                Some(self.collection_name),
+            )
+            .request_key(&self.request_key)
             .try_attach_auth_info(self.token)
             .send()
-            .await
-        {
-            Ok(_) => Ok(()),
-            Err(e) => Err(anyhow!("error: {}", e)),
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn file(&self, field: &str, file: FileUpload) -> Self {
+        let mut files = self.files.clone();
+        files.set(field, file);
+        Self {
+            files,
+            ..self.clone()
+        }
+    }
+
+    /// Adds `file` to a multi-file field, keeping the files already stored.
+    pub fn append_file(&self, field: &str, file: FileUpload) -> Self {
+        let mut files = self.files.clone();
+        files.append(field, file);
+        Self {
+            files,
+            ..self.clone()
+        }
+    }
+
+    pub fn remove_file(&self, field: &str, file_name: &str) -> Self {
+        let mut files = self.files.clone();
+        files.remove(field, file_name);
+        Self {
+            files,
+            ..self.clone()
+        }
+    }
+
+    pub fn request_key(&self, key: Option<&str>) -> Self {
+        Self {
+            request_key: RequestKey::from_option(key),
+            ..self.clone()
         }
     }
 }
@@ -324,6 +981,7 @@ impl<'a> RecordsManager<'a> {
         RecordViewRequestBuilder {
             client: self.http_client,
             token: self.token,
+            request_key: RequestKey::Auto,
             collection_name: self.collection_name,
             identifier,
             expand: None,
@@ -335,11 +993,24 @@ impl<'a> RecordsManager<'a> {
         RecordDeleteRequestBuilder {
             client: self.http_client,
             token: self.token,
+            request_key: RequestKey::Auto,
             collection_name: self.collection_name,
             identifier,
         }
     }
 
+    pub fn delete_all(&self) -> RecordDeleteAllRequestBuilder<'a> {
+        RecordDeleteAllRequestBuilder {
+            client: self.http_client,
+            token: self.token,
+            collection_name: self.collection_name,
+            filter: None,
+            concurrency: 4,
+            batch_size: Some(50),
+            on_progress: None,
+        }
+    }
+
     pub fn update<K: Serialize + Clone>(
         &self,
         identifier: &'a str,
@@ -348,9 +1019,11 @@ impl<'a> RecordsManager<'a> {
         RecordUpdateRequestBuilder {
             client: self.http_client,
             token: self.token,
+            request_key: RequestKey::Auto,
             collection_name: self.collection_name,
             id: identifier,
             data,
+            files: FileFields::default(),
         }
     }
 
@@ -358,8 +1031,10 @@ impl<'a> RecordsManager<'a> {
         RecordCreateRequestBuilder {
             client: self.http_client,
             token: self.token,
+            request_key: RequestKey::Auto,
             collection_name: self.collection_name,
             record,
+            files: FileFields::default(),
         }
     }
 
@@ -367,6 +1042,7 @@ impl<'a> RecordsManager<'a> {
         RecordsListRequestBuilder {
             client: self.http_client,
             token: self.token,
+            request_key: RequestKey::Auto,
             collection_name: self.collection_name,
             filter: None,
             sort: None,
@@ -374,6 +1050,8 @@ impl<'a> RecordsManager<'a> {
             fields: None,
             page: 1,
             per_page: 100,
+            skip_total: false,
+            concurrency: 1,
         }
     }
 
@@ -381,6 +1059,33 @@ impl<'a> RecordsManager<'a> {
         self.list().full_list::<T>().await
     }
 
+    /// Keyed by collection and filter, so lookups for different filters do
+    /// not cancel each other.
+    pub fn first_list_item(
+        &self,
+        filter: impl Into<Filter>,
+    ) -> RecordFirstListItemRequestBuilder<'a> {
+        let filter = filter.into();
+        let request_key = format!("one_by_filter_{}_{}", self.collection_name, filter);
+        RecordFirstListItemRequestBuilder {
+            list: RecordsListRequestBuilder {
+                request_key: RequestKey::Custom(request_key),
+                ..self.list().filter(filter).per_page(1).skip_total(true)
+            },
+        }
+    }
+
+    pub fn count(&self, filter: impl Into<Filter>) -> RecordCountRequestBuilder<'a> {
+        let filter = filter.into();
+        let request_key = format!("count_{}_{}", self.collection_name, filter);
+        RecordCountRequestBuilder {
+            list: RecordsListRequestBuilder {
+                request_key: RequestKey::Custom(request_key),
+                ..self.list().filter(filter).fields("id").per_page(1)
+            },
+        }
+    }
+
     pub async fn subscribe<F>(&self, topic: &str, callback: F) -> Result<()>
     where
         F: Fn(EventResponse) + Send + Sync + 'static,
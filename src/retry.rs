@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Method, Response, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// When and how often failed requests are sent again.
+///
+/// Connection failures, timeouts and `429`, `502`, `503` and `504` responses
+/// are retried with exponential backoff. A `Retry-After` header on the
+/// response takes precedence over the computed delay, and the response is
+/// returned as is when it asks for longer than `max_backoff`. Only idempotent
+/// methods are retried unless [`RetryPolicy::retry_non_idempotent`] is enabled.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            jitter: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send every request exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Total number of attempts, the first one included.
+    pub fn max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    /// Delay before the first retry, doubled on every further attempt up to
+    /// `max_backoff`.
+    pub fn backoff(self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            initial_backoff,
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// Randomize each delay between half and all of its computed value.
+    pub fn jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Also retry `POST` and `PATCH` requests, such as record creation. Only
+    /// enable this when a duplicated write is acceptable.
+    pub fn retry_non_idempotent(self, retry_non_idempotent: bool) -> Self {
+        Self {
+            retry_non_idempotent,
+            ..self
+        }
+    }
+
+    pub(crate) fn allows(&self, method: &Method) -> bool {
+        self.retry_non_idempotent
+            || matches!(
+                *method,
+                Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+            )
+    }
+
+    /// Delay before the next attempt, or `None` if `outcome` is final.
+    pub(crate) fn delay(
+        &self,
+        attempt: u32,
+        outcome: &Result<Response, reqwest::Error>,
+    ) -> Option<Duration> {
+        match outcome {
+            Ok(response) if is_retryable_status(response.status()) => match retry_after(response) {
+                Some(delay) if delay > self.max_backoff => None,
+                Some(delay) => Some(delay),
+                None => Some(self.backoff_for(attempt)),
+            },
+            Ok(_) => None,
+            Err(e) if e.is_connect() || e.is_timeout() || e.is_request() => {
+                Some(self.backoff_for(attempt))
+            }
+            Err(_) => None,
+        }
+    }
+
+    fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+
+        if !self.jitter {
+            return delay;
+        }
+
+        let half = delay / 2;
+        let random = RandomState::new().build_hasher().finish();
+        half + half.mul_f64((random % 1000) as f64 / 1000.0)
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// `Retry-After` as either delay seconds or an HTTP date.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    (date - Utc::now()).to_std().ok().or(Some(Duration::ZERO))
+}
@@ -0,0 +1,130 @@
+use crate::auth::AuthenticatedRequest;
+use crate::error::{Error, Result};
+use crate::httpc::{HttpClient, HttpRequest, RequestKey};
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::multipart::Form;
+use reqwest::{Method, Response};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug)]
+enum Body {
+    Empty,
+    Json(String),
+    Multipart(Form),
+}
+
+/// Request against an arbitrary PocketBase route, such as custom routes
+/// registered from JS hooks. It shares the client's base url, auth token,
+/// middlewares, retry policy and error handling.
+#[derive(Debug)]
+pub struct SendRequestBuilder<'a> {
+    pub(crate) client: &'a HttpClient,
+    pub(crate) token: Option<&'a str>,
+    pub(crate) request_key: RequestKey,
+    pub method: Method,
+    pub path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Result<Body>,
+}
+
+impl<'a> SendRequestBuilder<'a> {
+    pub(crate) fn new(
+        client: &'a HttpClient,
+        token: Option<&'a str>,
+        method: Method,
+        path: &str,
+    ) -> Self {
+        Self {
+            client,
+            token,
+            request_key: RequestKey::Auto,
+            method,
+            path: path.to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: Ok(Body::Empty),
+        }
+    }
+
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn json<T: Serialize>(mut self, body: &T) -> Self {
+        self.body = serde_json::to_string(body)
+            .map(Body::Json)
+            .map_err(Error::Encode);
+        self
+    }
+
+    pub fn multipart(mut self, form: Form) -> Self {
+        self.body = Ok(Body::Multipart(form));
+        self
+    }
+
+    pub fn request_key(mut self, key: Option<&str>) -> Self {
+        self.request_key = RequestKey::from_option(key);
+        self
+    }
+
+    fn prepare(self) -> Result<HttpRequest> {
+        let mut headers = Vec::with_capacity(self.headers.len());
+        for (name, value) in &self.headers {
+            match (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                (Ok(name), Ok(value)) => headers.push((name, value)),
+                _ => return Err(Error::Config(format!("invalid header `{}`", name))),
+            }
+        }
+
+        let body = self.body?;
+        let query = self.query;
+
+        Ok(self
+            .client
+            .request(self.method, &self.path)
+            .request_key(&self.request_key)
+            .try_attach_auth_info(self.token)
+            .map(|mut builder| {
+                if !query.is_empty() {
+                    builder = builder.query(&query);
+                }
+                for (name, value) in headers {
+                    builder = builder.header(name, value);
+                }
+                match body {
+                    Body::Empty => builder,
+                    Body::Json(json) => builder
+                        .header("Content-Type", "application/json")
+                        .body(json),
+                    Body::Multipart(form) => builder.multipart(form),
+                }
+            }))
+    }
+
+    /// Sends the request and returns the raw response of a 2xx status.
+    pub async fn response(self) -> Result<Response> {
+        self.prepare()?.send().await
+    }
+
+    /// Sends the request and decodes the JSON response body.
+    pub async fn call<T: DeserializeOwned>(self) -> Result<T> {
+        self.prepare()?.fetch::<T>().await
+    }
+
+    /// Sends the request and discards the response body.
+    pub async fn send(self) -> Result<()> {
+        self.response().await?;
+        Ok(())
+    }
+}
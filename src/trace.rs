@@ -0,0 +1,117 @@
+//! Span helpers behind the `tracing` feature. Without the feature every
+//! helper compiles down to nothing.
+//!
+//! Spans only ever record route templates, never concrete URLs, query
+//! strings or headers, so ids, filters and tokens stay out of traces.
+
+use reqwest::{Method, StatusCode};
+use std::future::Future;
+use std::time::Instant;
+
+/// Route template and collection of a request, e.g.
+/// `/api/collections/{collection}/records/{id}` and `posts`.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+pub(crate) struct Route {
+    pub(crate) template: Option<&'static str>,
+    pub(crate) collection: Option<String>,
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) type Span = tracing::Span;
+
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Span;
+
+#[cfg(feature = "tracing")]
+pub(crate) fn request(method: &Method, route: &Route) -> Span {
+    tracing::info_span!(
+        "pocketbase.request",
+        http.method = %method,
+        http.route = route.template,
+        pocketbase.collection = route.collection.as_deref(),
+        http.status_code = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        retries = tracing::field::Empty,
+    )
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn request(_method: &Method, _route: &Route) -> Span {
+    Span
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn finish(span: &Span, status: Option<StatusCode>, started: Instant, retries: u32) {
+    if let Some(status) = status {
+        span.record("http.status_code", status.as_u16());
+    }
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    span.record("retries", retries);
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn finish(_span: &Span, _status: Option<StatusCode>, _started: Instant, _retries: u32) {}
+
+#[cfg(feature = "tracing")]
+pub(crate) async fn instrument<F: Future>(span: &Span, future: F) -> F::Output {
+    use tracing::Instrument;
+    future.instrument(span.clone()).await
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) async fn instrument<F: Future>(_span: &Span, future: F) -> F::Output {
+    future.await
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn realtime_connect() -> Span {
+    tracing::info_span!("pocketbase.realtime.connect")
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn realtime_connect() -> Span {
+    Span
+}
+
+/// Marks the `PB_CONNECT` handshake inside the current connect span.
+#[cfg(feature = "tracing")]
+pub(crate) fn realtime_connected() {
+    tracing::info!("PB_CONNECT");
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn realtime_connected() {}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn realtime_submit(topics: usize) -> Span {
+    tracing::info_span!("pocketbase.realtime.submit", topics)
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn realtime_submit(_topics: usize) -> Span {
+    Span
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn realtime_dispatch(topic: &str, callbacks: usize) -> Span {
+    // Record topics are `collection/RECORD_ID`, keep the collection only.
+    let collection = topic.split(['/', '?']).next().unwrap_or_default();
+    tracing::info_span!("pocketbase.realtime.dispatch", collection, callbacks)
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn realtime_dispatch(_topic: &str, _callbacks: usize) -> Span {
+    Span
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn in_span<T>(span: &Span, f: impl FnOnce() -> T) -> T {
+    span.in_scope(f)
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn in_span<T>(_span: &Span, f: impl FnOnce() -> T) -> T {
+    f()
+}
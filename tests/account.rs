@@ -0,0 +1,118 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::auth::AuthStore;
+use pocketbase_sdk::client::Client;
+use serde_json::json;
+
+fn auth_store() -> AuthStore {
+    serde_json::from_value(json!({
+        "token": "token1",
+        "record": { "id": "u1", "collectionId": "c1", "collectionName": "users" }
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn password_reset() {
+    let server = MockServer::start();
+    let request_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/request-password-reset")
+            .json_body(json!({ "email": "ada@example.com" }));
+        then.status(204);
+    });
+    let confirm_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/confirm-password-reset")
+            .json_body(json!({
+                "token": "reset1",
+                "password": "new-secret",
+                "passwordConfirm": "new-secret"
+            }));
+        then.status(204);
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    client
+        .request_password_reset("users", "ada@example.com")
+        .await
+        .unwrap();
+    client
+        .confirm_password_reset("users", "reset1", "new-secret", "new-secret")
+        .await
+        .unwrap();
+
+    request_mock.assert();
+    confirm_mock.assert();
+}
+
+#[tokio::test]
+async fn verification_with_expired_token() {
+    let server = MockServer::start();
+    let request_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/request-verification")
+            .json_body(json!({ "email": "ada@example.com" }));
+        then.status(204);
+    });
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/confirm-verification")
+            .json_body(json!({ "token": "expired" }));
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 400,
+                "message": "An error occurred while validating the submitted data.",
+                "data": {
+                    "token": {
+                        "code": "validation_invalid_token",
+                        "message": "Invalid or expired token."
+                    }
+                }
+            }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    client
+        .request_verification("users", "ada@example.com")
+        .await
+        .unwrap();
+    let error = client
+        .confirm_verification("users", "expired")
+        .await
+        .unwrap_err();
+
+    request_mock.assert();
+    assert!(error.is_invalid_token());
+}
+
+#[tokio::test]
+async fn email_change() {
+    let server = MockServer::start();
+    let request_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/request-email-change")
+            .header("authorization", "token1")
+            .json_body(json!({ "newEmail": "ada@example.org" }));
+        then.status(204);
+    });
+    let confirm_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/confirm-email-change")
+            .json_body(json!({ "token": "change1", "password": "secret" }));
+        then.status(204);
+    });
+
+    let client = Client::new_with_auth(server.base_url().as_str(), auth_store()).unwrap();
+    client
+        .request_email_change("ada@example.org")
+        .await
+        .unwrap();
+    client
+        .confirm_email_change("users", "change1", "secret")
+        .await
+        .unwrap();
+
+    request_mock.assert();
+    confirm_mock.assert();
+}
@@ -0,0 +1,121 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, Serialize, Default, Deserialize)]
+pub struct Post {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    pub title: String,
+}
+
+#[tokio::test]
+async fn batch_success() {
+    let server = MockServer::start();
+    let batch_mock = server.mock(|when, then| {
+        when.method(POST).path("/api/batch").json_body(json!({
+            "requests": [
+                {
+                    "method": "POST",
+                    "url": "/api/collections/posts/records",
+                    "body": { "title": "first" }
+                },
+                {
+                    "method": "PATCH",
+                    "url": "/api/collections/posts/records/p2",
+                    "body": { "title": "second" }
+                },
+                {
+                    "method": "PUT",
+                    "url": "/api/collections/tags/records",
+                    "body": { "id": "t1", "title": "rust" }
+                },
+                {
+                    "method": "DELETE",
+                    "url": "/api/collections/comments/records/c1"
+                }
+            ]
+        }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!([
+                { "status": 200, "body": { "id": "p1", "title": "first" } },
+                { "status": 200, "body": { "id": "p2", "title": "second" } },
+                { "status": 200, "body": { "id": "t1", "title": "rust" } },
+                { "status": 204, "body": null }
+            ]));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let mut batch = client.batch();
+    batch
+        .collection("posts")
+        .create(&Post {
+            title: "first".to_string(),
+            ..Default::default()
+        })
+        .update("p2", &json!({ "title": "second" }));
+    batch.collection("tags").upsert(&Post {
+        id: "t1".to_string(),
+        title: "rust".to_string(),
+    });
+    batch.collection("comments").delete("c1");
+    assert_eq!(batch.len(), 4);
+
+    let results = batch.send().await.unwrap();
+
+    batch_mock.assert();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].decode::<Post>().unwrap().id, "p1");
+    assert_eq!(results[1].decode::<Post>().unwrap().title, "second");
+    assert_eq!(results[3].status, 204);
+}
+
+#[tokio::test]
+async fn batch_failure() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(POST).path("/api/batch");
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 400,
+                "message": "Batch transaction failed.",
+                "data": {
+                    "requests": {
+                        "1": {
+                            "code": "batch_request_failed",
+                            "message": "Batch request failed.",
+                            "response": {
+                                "status": 400,
+                                "message": "Failed to create record.",
+                                "data": {
+                                    "title": {
+                                        "code": "validation_required",
+                                        "message": "Missing required value."
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let mut batch = client.batch();
+    batch
+        .collection("posts")
+        .create(&json!({ "title": "ok" }))
+        .create(&json!({ "title": "" }));
+
+    let error = batch.send().await.unwrap_err();
+    let failures = error.api_error().unwrap().batch_failures();
+
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        failures[&1].validation_errors().get("title").unwrap().code,
+        "validation_required"
+    );
+}
@@ -0,0 +1,209 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::auth::Unauthorized;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::Error;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+async fn later<T>(future: impl Future<Output = T>) -> T {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    future.await
+}
+
+fn cancelling_client(server: &MockServer) -> Client<Unauthorized> {
+    Client::builder(server.base_url().as_str())
+        .auto_cancellation(true)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn newer_request_cancels_pending_duplicate() {
+    let server = mockserver();
+    let client = cancelling_client(&server);
+    let posts = client.collection("posts");
+    let stale = posts.list().filter("title ~ 'a'");
+    let fresh = posts.list().filter("title ~ 'ab'");
+
+    let (stale, fresh) = tokio::join!(stale.call::<Value>(), later(fresh.call::<Value>()));
+
+    assert!(matches!(stale, Err(Error::Cancelled)));
+    assert_eq!(fresh.unwrap().total_items, 1);
+}
+
+#[tokio::test]
+async fn distinct_request_keys_run_side_by_side() {
+    let server = mockserver();
+    let client = cancelling_client(&server);
+    let posts = client.collection("posts");
+    let first = posts.list().request_key(Some("sidebar"));
+    let second = posts.list().request_key(Some("search"));
+
+    let (first, second) = tokio::join!(first.call::<Value>(), later(second.call::<Value>()));
+
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+}
+
+#[tokio::test]
+async fn request_key_none_opts_out() {
+    let server = mockserver();
+    let client = cancelling_client(&server);
+    let posts = client.collection("posts");
+    let first = posts.list().request_key(None);
+    let second = posts.list();
+
+    let (first, second) = tokio::join!(first.call::<Value>(), later(second.call::<Value>()));
+
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+}
+
+#[tokio::test]
+async fn auto_cancellation_is_off_by_default() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+    let posts = client.collection("posts");
+    let alice = posts.list().filter("owner = 'alice'");
+    let bob = posts.list().filter("owner = 'bob'");
+
+    let (alice, bob) = tokio::join!(alice.call::<Value>(), later(bob.call::<Value>()));
+
+    assert!(alice.is_ok());
+    assert!(bob.is_ok());
+}
+
+#[tokio::test]
+async fn auto_cancellation_disabled() {
+    let server = mockserver();
+    let client = Client::builder(server.base_url().as_str())
+        .auto_cancellation(false)
+        .build()
+        .unwrap();
+    let posts = client.collection("posts");
+    let first = posts.list();
+    let second = posts.list();
+
+    let (first, second) = tokio::join!(first.call::<Value>(), later(second.call::<Value>()));
+
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+}
+
+#[tokio::test]
+async fn explicit_cancel() {
+    let server = mockserver();
+    let client = cancelling_client(&server);
+    let posts = client.collection("posts");
+    let search = posts.list().request_key(Some("search"));
+    let view = posts.view("1");
+
+    let (search, view, _) = tokio::join!(
+        search.call::<Value>(),
+        view.call::<Value>(),
+        later(async { client.cancel("search") })
+    );
+
+    assert!(matches!(search, Err(Error::Cancelled)));
+    assert!(view.is_ok());
+}
+
+#[tokio::test]
+async fn explicit_cancel_without_auto_cancellation() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+    let posts = client.collection("posts");
+    let first = posts.list().request_key(Some("search"));
+    let second = posts.list().request_key(Some("search"));
+    let list = posts.list();
+
+    let (first, second, list, _) = tokio::join!(
+        first.call::<Value>(),
+        later(second.call::<Value>()),
+        list.call::<Value>(),
+        later(later(async { client.cancel("search") }))
+    );
+
+    assert!(matches!(first, Err(Error::Cancelled)));
+    assert!(matches!(second, Err(Error::Cancelled)));
+    assert!(list.is_ok());
+}
+
+#[tokio::test]
+async fn cancel_all() {
+    let server = mockserver();
+    let client = cancelling_client(&server);
+    let posts = client.collection("posts");
+    let list = posts.list();
+    let view = posts.view("slow");
+
+    let (list, view, _) = tokio::join!(
+        list.call::<Value>(),
+        view.call::<Value>(),
+        later(async { client.cancel_all() })
+    );
+
+    assert!(matches!(list, Err(Error::Cancelled)));
+    assert!(matches!(view, Err(Error::Cancelled)));
+}
+
+#[tokio::test]
+async fn concurrent_writes_are_not_cancelled() {
+    let server = mockserver();
+    let client = cancelling_client(&server);
+    let posts = client.collection("posts");
+    let first = posts.create(json!({ "title": "a" }));
+    let second = posts.create(json!({ "title": "b" }));
+
+    let (first, second) = tokio::join!(first.call(), later(second.call()));
+
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+}
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records");
+        then.status(200)
+            .delay(Duration::from_millis(300))
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 100,
+                "totalItems": 1,
+                "items": [{ "id": "1" }]
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/1");
+        then.status(200)
+            .delay(Duration::from_millis(100))
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": "1" }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/slow");
+        then.status(200)
+            .delay(Duration::from_millis(300))
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": "slow" }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST).path("/api/collections/posts/records");
+        then.status(200)
+            .delay(Duration::from_millis(300))
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "id": "2",
+                "created": "2024-01-01 00:00:00.000Z",
+                "updated": "2024-01-01 00:00:00.000Z"
+            }));
+    });
+
+    server
+}
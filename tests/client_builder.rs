@@ -0,0 +1,93 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::Error;
+use pocketbase_sdk::reqwest;
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn default_headers_and_user_agent() {
+    let mockserver = mockserver();
+    let client = Client::builder(mockserver.base_url().as_str())
+        .user_agent("my-service/1.0")
+        .default_header("Accept-Language", "de-DE")
+        .build()
+        .unwrap();
+
+    let health = client.health_check().await;
+    assert!(health.is_ok());
+}
+
+#[tokio::test]
+async fn custom_http_client() {
+    let mockserver = mockserver();
+    let http_client = reqwest::Client::builder()
+        .user_agent("my-service/1.0")
+        .build()
+        .unwrap();
+    let client = Client::builder(mockserver.base_url().as_str())
+        .http_client(http_client)
+        .default_header("Accept-Language", "de-DE")
+        .build()
+        .unwrap();
+
+    let health = client.health_check().await;
+    assert!(health.is_ok());
+}
+
+#[tokio::test]
+async fn request_timeout() {
+    let mockserver = mockserver();
+    let client = Client::builder(mockserver.base_url().as_str())
+        .timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    let error = client
+        .collection("slow")
+        .view("1")
+        .call::<serde_json::Value>()
+        .await
+        .unwrap_err();
+
+    match error {
+        Error::Transport(e) => assert!(e.is_timeout()),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn invalid_header() {
+    let result = Client::builder("http://localhost:8090")
+        .default_header("Accept-Language", "de\nDE")
+        .build();
+
+    assert!(matches!(result, Err(Error::Config(_))));
+}
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/health")
+            .header("user-agent", "my-service/1.0")
+            .header("accept-language", "de-DE");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "code": 200,
+                "message": "API is healthy."
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/slow/records/1");
+        then.status(200)
+            .delay(Duration::from_millis(500))
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": "1" }));
+    });
+
+    server
+}
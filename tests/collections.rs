@@ -25,6 +25,49 @@ async fn colletion_view_succes() {
     assert!(collection.is_ok())
 }
 
+#[tokio::test]
+async fn collection_create_success() {
+    let server = mockserver();
+    let create_mock = server.mock(|when, then| {
+        when.method(POST).path("/api/collections").json_body(json!({
+            "name": "notes",
+            "type": "base",
+            "schema": [],
+            "system": false,
+            "listRule": null,
+            "viewRule": null,
+            "createRule": null,
+            "updateRule": null,
+            "deleteRule": null,
+            "indexes": []
+        }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "id": "n1",
+                "created": "2022-06-22 07:13:00.643Z",
+                "updated": "2022-06-22 07:13:00.643Z",
+                "name": "notes",
+                "type": "base",
+                "schema": []
+            }));
+    });
+    let admin_client = Client::new(server.base_url().as_str())
+        .auth_with_password("_superusers", "sreedev@icloud.com", "Sreedev123")
+        .await
+        .unwrap();
+
+    let collection = admin_client
+        .collections()
+        .create("notes")
+        .call()
+        .await
+        .unwrap();
+
+    create_mock.assert();
+    assert_eq!(collection.name, "notes");
+}
+
 fn mockserver() -> MockServer {
     let server = MockServer::start();
     server.mock(|when, then| {
@@ -0,0 +1,118 @@
+use futures_util::TryStreamExt;
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::cursor::Cursor;
+use pocketbase_sdk::error::Error;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+const AFTER_B: &str = "(published = true) && (created > '2024-01-01 10:00:00.000Z' || created = '2024-01-01 10:00:00.000Z' && id > 'b')";
+
+#[derive(Debug, Deserialize)]
+struct Post {
+    id: String,
+}
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("filter", "published = true")
+            .query_param("sort", "created,id")
+            .query_param("skipTotal", "1")
+            .query_param("perPage", "2")
+            .query_param("page", "1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 2,
+                "totalItems": -1,
+                "items": [
+                    { "id": "a", "created": "2024-01-01 09:00:00.000Z" },
+                    { "id": "b", "created": "2024-01-01 10:00:00.000Z" }
+                ]
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("filter", AFTER_B)
+            .query_param("page", "1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 2,
+                "totalItems": -1,
+                "items": [{ "id": "c", "created": "2024-01-01 10:00:00.000Z" }]
+            }));
+    });
+
+    server
+}
+
+#[tokio::test]
+async fn keyset_scan() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let pages: Vec<_> = client
+        .collection("posts")
+        .list()
+        .filter("published = true")
+        .per_page(2)
+        .keyset()
+        .into_page_stream::<Post>()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(pages.len(), 2);
+    assert!(pages[0].has_more);
+    assert!(!pages[1].has_more);
+    let ids: Vec<_> = pages
+        .iter()
+        .flat_map(|page| page.items.iter().map(|post| post.id.as_str()))
+        .collect();
+    assert_eq!(ids, vec!["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn resume_from_saved_cursor() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+    let scan = client
+        .collection("posts")
+        .list()
+        .filter("published = true")
+        .per_page(2)
+        .keyset();
+
+    let first = scan.call::<Post>().await.unwrap();
+    let saved = serde_json::to_string(&first.cursor.unwrap()).unwrap();
+    let cursor: Cursor = serde_json::from_str(&saved).unwrap();
+    let second = scan.after(cursor).call::<Post>().await.unwrap();
+
+    assert_eq!(second.items[0].id, "c");
+    assert!(!second.has_more);
+}
+
+#[tokio::test]
+async fn keyset_rejects_sort() {
+    let client = Client::new("http://localhost:8090");
+
+    let error = client
+        .collection("posts")
+        .list()
+        .sort("-title")
+        .keyset()
+        .call::<Value>()
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Query(_)));
+}
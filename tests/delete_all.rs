@@ -0,0 +1,117 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use serde_json::json;
+use std::sync::Mutex;
+
+fn mock_ids(server: &MockServer) {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("filter", "draft = true")
+            .query_param("fields", "id")
+            .query_param("sort", "id");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 500,
+                "totalItems": 3,
+                "items": [{ "id": "a" }, { "id": "b" }, { "id": "c" }]
+            }));
+    });
+}
+
+#[tokio::test]
+async fn delete_all_in_batches() {
+    let server = MockServer::start();
+    mock_ids(&server);
+    let first_batch = server.mock(|when, then| {
+        when.method(POST).path("/api/batch").json_body(json!({
+            "requests": [
+                { "method": "DELETE", "url": "/api/collections/posts/records/a" },
+                { "method": "DELETE", "url": "/api/collections/posts/records/b" }
+            ]
+        }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!([{ "status": 204 }, { "status": 204 }]));
+    });
+    let second_batch = server.mock(|when, then| {
+        when.method(POST).path("/api/batch").json_body(json!({
+            "requests": [
+                { "method": "DELETE", "url": "/api/collections/posts/records/c" }
+            ]
+        }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!([{ "status": 204 }]));
+    });
+
+    let progress = Mutex::new(vec![]);
+    let client = Client::new(server.base_url().as_str());
+    let summary = client
+        .collection("posts")
+        .delete_all()
+        .filter("draft = true")
+        .batch_size(Some(2))
+        .on_progress(|p| progress.lock().unwrap().push(p.deleted))
+        .call()
+        .await
+        .unwrap();
+
+    first_batch.assert();
+    second_batch.assert();
+    assert_eq!(summary.deleted, vec!["a", "b", "c"]);
+    assert!(summary.failed.is_empty());
+    assert_eq!(*progress.lock().unwrap(), vec![2, 3]);
+}
+
+#[tokio::test]
+async fn delete_all_falls_back_without_batch_support() {
+    let server = MockServer::start();
+    mock_ids(&server);
+    server.mock(|when, then| {
+        when.method(POST).path("/api/batch");
+        then.status(403)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 403,
+                "message": "Batch requests are not allowed.",
+                "data": {}
+            }));
+    });
+    for id in ["a", "c"] {
+        server.mock(|when, then| {
+            when.method(DELETE)
+                .path(format!("/api/collections/posts/records/{}", id));
+            then.status(204);
+        });
+    }
+    server.mock(|when, then| {
+        when.method(DELETE).path("/api/collections/posts/records/b");
+        then.status(404)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 404,
+                "message": "The requested resource wasn't found.",
+                "data": {}
+            }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let summary = client
+        .collection("posts")
+        .delete_all()
+        .filter("draft = true")
+        .concurrency(2)
+        .call()
+        .await
+        .unwrap();
+
+    let mut deleted = summary.deleted.clone();
+    deleted.sort();
+    assert_eq!(deleted, vec!["a", "c"]);
+    assert_eq!(summary.failed.len(), 1);
+    assert_eq!(summary.failed[0].0, "b");
+    assert!(summary.failed[0].1.is_not_found());
+}
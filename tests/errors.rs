@@ -0,0 +1,197 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::{Error, FieldError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, Serialize, Default, Deserialize)]
+pub struct Post {
+    pub id: String,
+    pub title: String,
+}
+
+#[tokio::test]
+async fn view_not_found() {
+    let mockserver = mockserver();
+    let client = Client::new(mockserver.base_url().as_str());
+
+    let error = client
+        .collection("posts")
+        .view("missing")
+        .call::<Post>()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_not_found());
+    match error {
+        Error::Api(e) => {
+            assert_eq!(e.status, 404);
+            assert_eq!(e.message, "The requested resource wasn't found.");
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn create_validation_failed() {
+    let mockserver = mockserver();
+    let client = Client::new(mockserver.base_url().as_str());
+
+    let error = client
+        .collection("posts")
+        .create(json!({ "title": "" }))
+        .call()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_validation());
+    let field_errors = error.api_error().unwrap().field_errors();
+    assert_eq!(
+        field_errors.get("title"),
+        Some(&FieldError {
+            code: "validation_required".to_string(),
+            message: "Missing required value.".to_string(),
+        })
+    );
+}
+
+#[tokio::test]
+async fn update_nested_validation_errors() {
+    let mockserver = mockserver();
+    let client = Client::new(mockserver.base_url().as_str());
+
+    let error = client
+        .collection("posts")
+        .update("1", json!({ "title": "", "meta": { "tags": ["ok", ""] } }))
+        .call::<Post>()
+        .await
+        .unwrap_err();
+
+    let errors = error.validation_errors().unwrap();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors.get("title").unwrap().code, "validation_required");
+    assert_eq!(
+        errors.nested("meta").get("tags.1").unwrap().code,
+        "validation_min_text_constraint"
+    );
+    assert_eq!(
+        errors.to_string(),
+        "meta.tags.1: validation_min_text_constraint, title: validation_required"
+    );
+}
+
+#[tokio::test]
+async fn update_send_reports_failure() {
+    let mockserver = mockserver();
+    let client = Client::new(mockserver.base_url().as_str());
+
+    let result = client
+        .collection("posts")
+        .update("1", json!({ "title": "" }))
+        .send()
+        .await;
+
+    assert!(result.unwrap_err().is_validation());
+}
+
+#[tokio::test]
+async fn non_json_error_body() {
+    let mockserver = mockserver();
+    let client = Client::new(mockserver.base_url().as_str());
+
+    let error = client
+        .collection("posts")
+        .list()
+        .call::<Post>()
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.status(), Some(502));
+    assert_eq!(error.api_error().unwrap().message, "Bad Gateway");
+}
+
+#[tokio::test]
+async fn decode_failure() {
+    let mockserver = mockserver();
+    let client = Client::new(mockserver.base_url().as_str());
+
+    let error = client
+        .collection("posts")
+        .view("malformed")
+        .call::<Post>()
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Decode(_)));
+}
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records/missing");
+        then.status(404)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 404,
+                "message": "The requested resource wasn't found.",
+                "data": {}
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records/malformed");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": 1 }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST).path("/api/collections/posts/records");
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 400,
+                "message": "Failed to create record.",
+                "data": {
+                    "title": {
+                        "code": "validation_required",
+                        "message": "Missing required value."
+                    }
+                }
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(PATCH).path("/api/collections/posts/records/1");
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 400,
+                "message": "Failed to update record.",
+                "data": {
+                    "title": {
+                        "code": "validation_required",
+                        "message": "Missing required value."
+                    },
+                    "meta": {
+                        "tags": {
+                            "1": {
+                                "code": "validation_min_text_constraint",
+                                "message": "Must be at least 1 character(s)."
+                            }
+                        }
+                    }
+                }
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records");
+        then.status(502).body("");
+    });
+
+    server
+}
@@ -0,0 +1,225 @@
+use chrono::{TimeZone, Utc};
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::Error;
+use pocketbase_sdk::filter::{Filter, collection, field, request};
+use serde_json::{Value, json};
+
+#[test]
+fn comparisons() {
+    let created = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+    assert_eq!(field("views").gte(10).to_string(), "views >= 10");
+    assert_eq!(field("price").lt(9.5).to_string(), "price < 9.5");
+    assert_eq!(
+        field("published").ne(false).to_string(),
+        "published != false"
+    );
+    assert_eq!(
+        field("deleted").eq(None::<&str>).to_string(),
+        "deleted = null"
+    );
+    assert_eq!(
+        field("created").gt(created).to_string(),
+        "created > '2024-01-02 03:04:05.000Z'"
+    );
+    assert_eq!(
+        field("title").not_like("draft").to_string(),
+        "title !~ 'draft'"
+    );
+    assert_eq!(
+        field("tags.name").any_eq("rust").to_string(),
+        "tags.name ?= 'rust'"
+    );
+    assert_eq!(
+        collection("members.user")
+            .eq(request("auth.id"))
+            .to_string(),
+        "@collection.members.user = @request.auth.id"
+    );
+}
+
+#[test]
+fn values_are_escaped() {
+    let filter = field("title").like("' || id != '");
+
+    assert_eq!(filter.to_string(), r"title ~ '\' || id != \''");
+
+    let filter = field("title")
+        .eq(r"x\y")
+        .and(field("owner").eq(" || 1=1 || "));
+    assert_eq!(
+        filter.to_string(),
+        r"title = 'x\y' && owner = ' || 1=1 || '"
+    );
+}
+
+#[test]
+fn numbers_keep_their_precision() {
+    assert_eq!(
+        field("big").eq(9007199254740993_i64).to_string(),
+        "big = 9007199254740993"
+    );
+    assert_eq!(
+        field("big").eq(u64::MAX).to_string(),
+        "big = 18446744073709551615"
+    );
+    assert_eq!(
+        field("big").eq(json!(9007199254740993_u64)).to_string(),
+        "big = 9007199254740993"
+    );
+    assert_eq!(field("views").gt([1, 2].len()).to_string(), "views > 2");
+    assert_eq!(field("offset").lt(-3_isize).to_string(), "offset < -3");
+    assert_eq!(field("score").gte(0.1_f32).to_string(), "score >= 0.1");
+    assert_eq!(
+        field("big").eq(u64::MAX as u128).to_string(),
+        "big = 18446744073709551615"
+    );
+    assert_eq!(
+        field("small").eq(i64::MIN as i128).to_string(),
+        "small = -9223372036854775808"
+    );
+}
+
+#[tokio::test]
+async fn wide_integers_are_checked() {
+    let server = MockServer::start();
+    let client = Client::new(server.base_url().as_str());
+    let posts = client.collection("posts");
+    for filter in [
+        field("big").eq(u64::MAX as u128 + 1),
+        field("big").eq(u128::MAX),
+        field("small").eq(i64::MIN as i128 - 1),
+        field("score").gt(f32::NAN),
+    ] {
+        let result = posts.list().filter(filter).call::<Value>().await;
+        assert!(matches!(result, Err(Error::Query(_))), "{:?}", result);
+    }
+}
+
+#[tokio::test]
+async fn unrepresentable_values_are_rejected() {
+    let server = MockServer::start();
+    let list_mock = server.mock(|when, then| {
+        when.method(GET).path("/api/collections/files/records");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "page": 1, "perPage": 100, "totalItems": 0, "items": [] }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let files = client.collection("files");
+    let filters = [
+        field("path").like("C:\\"),
+        field("size").gt(f64::NAN),
+        field("size").lt(f64::INFINITY).or(field("size").eq(0)),
+        Filter::bind("path = {:path}", [("path", "C:\\".into())]),
+    ];
+    for filter in filters {
+        let result = files.list().filter(filter).call::<Value>().await;
+        assert!(matches!(result, Err(Error::Query(_))), "{:?}", result);
+    }
+
+    let result = files
+        .delete_all()
+        .filter(field("path").like("C:\\"))
+        .call()
+        .await;
+    assert!(matches!(result, Err(Error::Query(_))));
+
+    list_mock.assert_calls(0);
+}
+
+#[test]
+fn grouping() {
+    let filter = field("status")
+        .eq("active")
+        .and(field("role").eq("admin").or(field("role").eq("editor")));
+
+    assert_eq!(
+        filter.to_string(),
+        "status = 'active' && (role = 'admin' || role = 'editor')"
+    );
+    assert_eq!(
+        field("a")
+            .eq(1)
+            .and(field("b").eq(2))
+            .or(field("c").eq(3))
+            .to_string(),
+        "a = 1 && b = 2 || c = 3"
+    );
+    assert_eq!(
+        field("a").eq(1).or(field("b").eq(2)).group().to_string(),
+        "(a = 1 || b = 2)"
+    );
+}
+
+#[test]
+fn bind_params() {
+    let filter = Filter::bind(
+        "title ~ {:title} && author = {:author} && {:missing}",
+        [
+            ("title", "it's".into()),
+            ("author", request("auth.id").into()),
+        ],
+    );
+
+    assert_eq!(
+        filter.to_string(),
+        r"title ~ 'it\'s' && author = @request.auth.id && {:missing}"
+    );
+}
+
+#[test]
+fn raw_and_bound_operands_are_grouped() {
+    let filter = Filter::bind(
+        "a = {:x} || b = {:y}",
+        [("x", "1".into()), ("y", "2".into())],
+    )
+    .and(field("tenant").eq("t1"));
+    assert_eq!(filter.to_string(), "(a = '1' || b = '2') && tenant = 't1'");
+
+    let filter = field("tenant")
+        .eq("t1")
+        .and(Filter::raw("public = true || owner = 'x'"));
+    assert_eq!(
+        filter.to_string(),
+        "tenant = 't1' && (public = true || owner = 'x')"
+    );
+
+    assert_eq!(Filter::raw("a = 1 || b = 2").to_string(), "a = 1 || b = 2");
+}
+
+#[test]
+fn bind_does_not_fill_placeholders_in_values() {
+    let filter = Filter::bind(
+        "title = {:a} && owner = {:b}",
+        [("a", "{:b}".into()), ("b", " || id != ".into())],
+    );
+
+    assert_eq!(filter.to_string(), "title = '{:b}' && owner = ' || id != '");
+}
+
+#[tokio::test]
+async fn list_with_filter() {
+    let server = MockServer::start();
+    let list_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("filter", "status = 'active' && views > 10");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "page": 1, "perPage": 100, "totalItems": 0, "items": [] }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    client
+        .collection("posts")
+        .list()
+        .filter(field("status").eq("active").and(field("views").gt(10)))
+        .call::<Value>()
+        .await
+        .unwrap();
+
+    list_mock.assert();
+}
@@ -0,0 +1,131 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::filter::field;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Default, Deserialize)]
+struct Post {
+    id: String,
+    title: String,
+}
+
+#[tokio::test]
+async fn first_list_item() {
+    let server = MockServer::start();
+    let list_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("filter", "slug = 'hello'")
+            .query_param("expand", "author")
+            .query_param("perPage", "1")
+            .query_param("skipTotal", "1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 1,
+                "totalItems": -1,
+                "items": [{ "id": "p1", "title": "Hello" }]
+            }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let post = client
+        .collection("posts")
+        .first_list_item(field("slug").eq("hello"))
+        .expand("author")
+        .call::<Post>()
+        .await
+        .unwrap();
+
+    list_mock.assert();
+    assert_eq!(post.id, "p1");
+    assert_eq!(post.title, "Hello");
+}
+
+#[tokio::test]
+async fn first_list_item_not_found() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "page": 1, "perPage": 1, "totalItems": -1, "items": [] }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let error = client
+        .collection("posts")
+        .first_list_item("slug = 'missing'")
+        .call::<Post>()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_not_found());
+}
+
+#[tokio::test]
+async fn count() {
+    let server = MockServer::start();
+    let count_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("filter", "published = true")
+            .query_param("fields", "id")
+            .query_param("perPage", "1")
+            .query_param_missing("skipTotal");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 1,
+                "totalItems": 42,
+                "items": [{ "id": "p1" }]
+            }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let count = client
+        .collection("posts")
+        .count("published = true")
+        .call()
+        .await
+        .unwrap();
+
+    count_mock.assert();
+    assert_eq!(count, 42);
+}
+
+#[tokio::test]
+async fn count_with_expand_and_fields() {
+    let server = MockServer::start();
+    let count_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("expand", "author")
+            .query_param("fields", "title")
+            .query_param("perPage", "1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 1,
+                "totalItems": 7,
+                "items": [{ "title": "Hello" }]
+            }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let count = client
+        .collection("posts")
+        .count("published = true")
+        .expand("author")
+        .fields("title")
+        .call()
+        .await
+        .unwrap();
+
+    count_mock.assert();
+    assert_eq!(count, 7);
+}
@@ -0,0 +1,93 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Default, Deserialize)]
+struct Post {
+    id: String,
+}
+
+fn mock_page(server: &MockServer, page: usize, total: usize, ids: &[&str]) {
+    let items: Vec<_> = ids.iter().map(|id| json!({ "id": id })).collect();
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("perPage", "2")
+            .query_param("page", page.to_string());
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": page,
+                "perPage": 2,
+                "totalItems": total,
+                "items": items
+            }));
+    });
+}
+
+#[tokio::test]
+async fn concurrent_full_list_keeps_order() {
+    let server = MockServer::start();
+    mock_page(&server, 1, 5, &["a", "b"]);
+    mock_page(&server, 2, 5, &["c", "d"]);
+    mock_page(&server, 3, 5, &["e"]);
+    let client = Client::new(server.base_url().as_str());
+
+    let posts = client
+        .collection("posts")
+        .list()
+        .per_page(2)
+        .concurrency(3)
+        .full_list::<Post>()
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = posts.iter().map(|post| post.id.as_str()).collect();
+    assert_eq!(ids, vec!["a", "b", "c", "d", "e"]);
+}
+
+#[tokio::test]
+async fn concurrent_full_list_handles_inserts() {
+    let server = MockServer::start();
+    mock_page(&server, 1, 5, &["a", "b"]);
+    // "x" was inserted before "c" after page 1 was read, shifting "d"
+    // onto page 3 and "e" onto a page that was not planned.
+    mock_page(&server, 2, 6, &["x", "c"]);
+    mock_page(&server, 3, 6, &["d", "e"]);
+    mock_page(&server, 4, 6, &[]);
+    let client = Client::new(server.base_url().as_str());
+
+    let posts = client
+        .collection("posts")
+        .list()
+        .per_page(2)
+        .concurrency(2)
+        .full_list::<Post>()
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = posts.iter().map(|post| post.id.as_str()).collect();
+    assert_eq!(ids, vec!["a", "b", "x", "c", "d", "e"]);
+}
+
+#[tokio::test]
+async fn concurrent_full_list_drops_duplicates() {
+    let server = MockServer::start();
+    mock_page(&server, 1, 4, &["a", "b"]);
+    // Writes between the two reads shifted the rows, so page 2 repeats "b".
+    mock_page(&server, 2, 4, &["b", "d"]);
+    let client = Client::new(server.base_url().as_str());
+
+    let posts = client
+        .collection("posts")
+        .list()
+        .per_page(2)
+        .concurrency(2)
+        .full_list::<Post>()
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = posts.iter().map(|post| post.id.as_str()).collect();
+    assert_eq!(ids, vec!["a", "b", "d"]);
+}
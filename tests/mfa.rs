@@ -0,0 +1,96 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::Error;
+use serde_json::json;
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/_superusers/auth-with-password")
+            .json_body(json!({ "identity": "admin@example.com", "password": "secret" }));
+        then.status(401)
+            .header("content-type", "application/json")
+            .json_body(json!({ "mfaId": "mfa1" }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/_superusers/request-otp")
+            .json_body(json!({ "email": "admin@example.com" }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "otpId": "otp1" }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/_superusers/auth-with-otp")
+            .json_body(json!({ "otpId": "otp1", "password": "123456", "mfaId": "mfa1" }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "token": "token1",
+                "record": { "id": "a1", "collectionId": "c0", "collectionName": "_superusers" }
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/_superusers/auth-with-password")
+            .json_body(json!({ "identity": "admin@example.com", "password": "wrong" }));
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 400,
+                "message": "Failed to authenticate.",
+                "data": {}
+            }));
+    });
+
+    server
+}
+
+#[tokio::test]
+async fn password_then_otp() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let error = client
+        .auth_with_password("_superusers", "admin@example.com", "secret")
+        .await
+        .err()
+        .unwrap();
+    let Error::MfaRequired { mfa_id } = error else {
+        panic!("expected MfaRequired, got {:?}", error);
+    };
+    assert_eq!(mfa_id, "mfa1");
+
+    let otp_id = client
+        .request_otp("_superusers", "admin@example.com")
+        .await
+        .unwrap();
+    let client = client
+        .with_mfa(&mfa_id)
+        .auth_with_otp("_superusers", &otp_id, "123456")
+        .await
+        .unwrap();
+
+    assert_eq!(client.auth().token(), Some("token1"));
+}
+
+#[tokio::test]
+async fn failed_first_factor_is_not_mfa() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let error = client
+        .auth_with_password("_superusers", "admin@example.com", "wrong")
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::Api(_)));
+    assert_eq!(error.status(), Some(400));
+}
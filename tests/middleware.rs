@@ -0,0 +1,191 @@
+use bytes::Bytes;
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::{Error, Result};
+use pocketbase_sdk::middleware::Middleware;
+use pocketbase_sdk::reqwest::header::HeaderMap;
+use pocketbase_sdk::reqwest::{Request, Response, StatusCode};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+
+struct TenantHeader(&'static str);
+
+impl Middleware for TenantHeader {
+    fn before_send(&self, request: &mut Request) -> Result<()> {
+        request
+            .headers_mut()
+            .insert("X-Tenant", self.0.parse().unwrap());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+struct AuditLog(Arc<Mutex<Vec<String>>>);
+
+impl Middleware for AuditLog {
+    fn before_send(&self, request: &mut Request) -> Result<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", request.method(), request.url().path()));
+        Ok(())
+    }
+
+    fn after_send(&self, response: Response) -> Result<Response> {
+        self.0
+            .lock()
+            .unwrap()
+            .push(response.status().as_u16().to_string());
+        Ok(response)
+    }
+}
+
+struct ReadOnly;
+
+impl Middleware for ReadOnly {
+    fn before_send(&self, request: &mut Request) -> Result<()> {
+        if request.method() != "GET" {
+            return Err(Error::Middleware("read only client".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Unwraps records a gateway wrapped as `{ "data": .. }`.
+struct Unwrap;
+
+impl Middleware for Unwrap {
+    fn transform_body(
+        &self,
+        _status: StatusCode,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) -> Result<Bytes> {
+        if headers.get("x-wrapped").is_none() {
+            return Ok(body);
+        }
+        let mut wrapped: Value = serde_json::from_slice(&body).map_err(Error::Decode)?;
+        Ok(wrapped["data"].take().to_string().into())
+    }
+}
+
+#[tokio::test]
+async fn middlewares_see_every_request() {
+    let server = mockserver();
+    let audit_log = AuditLog::default();
+    let client = Client::builder(server.base_url().as_str())
+        .middleware(TenantHeader("acme"))
+        .middleware(audit_log.clone())
+        .build()
+        .unwrap();
+
+    let record = client
+        .collection("posts")
+        .view("1")
+        .call::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(record["id"], "1");
+
+    let error = client
+        .collection("posts")
+        .view("2")
+        .call::<Value>()
+        .await
+        .unwrap_err();
+    assert!(error.is_not_found());
+
+    assert_eq!(
+        *audit_log.0.lock().unwrap(),
+        vec![
+            "GET /api/collections/posts/records/1",
+            "200",
+            "GET /api/collections/posts/records/2",
+            "404",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn middleware_rejects_request() {
+    let server = mockserver();
+    let client = Client::builder(server.base_url().as_str())
+        .middleware(ReadOnly)
+        .build()
+        .unwrap();
+
+    let result = client
+        .collection("posts")
+        .create(json!({ "title": "a" }))
+        .call()
+        .await;
+
+    assert!(matches!(result, Err(Error::Middleware(_))));
+}
+
+#[tokio::test]
+async fn middleware_rewrites_body() {
+    let server = mockserver();
+    let client = Client::builder(server.base_url().as_str())
+        .middleware(Unwrap)
+        .build()
+        .unwrap();
+
+    let record = client
+        .collection("posts")
+        .view("3")
+        .call::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(record, json!({ "id": "3" }));
+
+    let error = client
+        .collection("posts")
+        .view("4")
+        .call::<Value>()
+        .await
+        .unwrap_err();
+    assert_eq!(error.api_error().unwrap().message, "Gone fishing.");
+}
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records/1")
+            .header("x-tenant", "acme");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": "1" }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/2");
+        then.status(404)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 404,
+                "message": "The requested resource wasn't found.",
+                "data": {}
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/3");
+        then.status(200)
+            .header("content-type", "application/json")
+            .header("x-wrapped", "1")
+            .json_body(json!({ "data": { "id": "3" } }));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/4");
+        then.status(503)
+            .header("content-type", "application/json")
+            .header("x-wrapped", "1")
+            .json_body(json!({ "data": { "status": 503, "message": "Gone fishing." } }));
+    });
+
+    server
+}
@@ -0,0 +1,116 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct User {
+    email: String,
+}
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/users/auth-methods");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "password": { "enabled": true, "identityFields": ["email"] },
+                "oauth2": {
+                    "enabled": true,
+                    "providers": [{
+                        "name": "github",
+                        "displayName": "GitHub",
+                        "state": "s1",
+                        "authURL": "https://github.com/login/oauth/authorize?client_id=abc&redirect_uri=",
+                        "codeVerifier": "v1",
+                        "codeChallenge": "c1",
+                        "codeChallengeMethod": "S256"
+                    }]
+                },
+                "mfa": { "enabled": false, "duration": 0 },
+                "otp": { "enabled": true, "duration": 180 }
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/auth-with-oauth2")
+            .json_body(json!({
+                "provider": "github",
+                "code": "code1",
+                "codeVerifier": "v1",
+                "redirectURL": "http://127.0.0.1:9000/callback",
+                "createData": { "email": "ada@example.com" }
+            }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "token": "token1",
+                "record": {
+                    "id": "u1",
+                    "collectionId": "c1",
+                    "collectionName": "users",
+                    "email": "ada@example.com"
+                },
+                "meta": {
+                    "id": "gh1",
+                    "name": "Ada",
+                    "username": "ada",
+                    "email": "ada@example.com",
+                    "isNew": true,
+                    "avatarURL": "https://example.com/ada.png",
+                    "accessToken": "at",
+                    "refreshToken": "",
+                    "expiry": "2024-01-01 00:00:00.000Z",
+                    "rawUser": { "login": "ada" }
+                }
+            }));
+    });
+
+    server
+}
+
+#[tokio::test]
+async fn list_auth_methods() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let methods = client.list_auth_methods("users").await.unwrap();
+
+    assert_eq!(methods.password.identity_fields, vec!["email"]);
+    assert!(methods.otp.enabled);
+    let provider = &methods.oauth2.providers[0];
+    assert_eq!(provider.name, "github");
+    assert_eq!(provider.code_verifier, "v1");
+    assert_eq!(
+        provider.auth_url_with_redirect("http://127.0.0.1:9000/callback"),
+        "https://github.com/login/oauth/authorize?client_id=abc&redirect_uri=http%3A%2F%2F127%2E0%2E0%2E1%3A9000%2Fcallback"
+    );
+}
+
+#[tokio::test]
+async fn auth_with_oauth2_code() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let (client, meta) = client
+        .auth_with_oauth2_code(
+            "users",
+            "github",
+            "code1",
+            "v1",
+            "http://127.0.0.1:9000/callback",
+            Some(json!({ "email": "ada@example.com" })),
+        )
+        .await
+        .unwrap();
+
+    assert!(meta.is_new);
+    assert_eq!(meta.avatar_url, "https://example.com/ada.png");
+    assert_eq!(meta.raw_user["login"], "ada");
+    let (base, user) = client.auth().record::<User>().unwrap();
+    assert_eq!(base.collection_name, "users");
+    assert_eq!(user.email, "ada@example.com");
+}
@@ -0,0 +1,178 @@
+#![cfg(feature = "oauth2-loopback")]
+
+use httpmock::prelude::*;
+use percent_encoding::percent_decode_str;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::Error;
+use serde_json::json;
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+    let auth_url = format!(
+        "{}/authorize?client_id=abc&redirect_uri=",
+        server.base_url()
+    );
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/users/auth-methods");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "oauth2": {
+                    "enabled": true,
+                    "providers": [{
+                        "name": "github",
+                        "state": "s1",
+                        "authURL": auth_url,
+                        "codeVerifier": "v1"
+                    }]
+                }
+            }));
+    });
+
+    // The mock provider, standing in for the consent screen.
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/authorize")
+            .query_param_exists("redirect_uri");
+        then.status(200);
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/auth-with-oauth2")
+            .json_body_includes(
+                json!({ "provider": "github", "code": "code1", "codeVerifier": "v1" }).to_string(),
+            );
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "token": "token1",
+                "record": { "id": "u1", "collectionId": "c1", "collectionName": "users" },
+                "meta": { "isNew": false, "name": "Ada" }
+            }));
+    });
+
+    server
+}
+
+/// Acts as the browser: visits the provider, then follows its redirect back
+/// to the loopback listener with the given query.
+fn browser(query: &'static str) -> impl Fn(&str) + Send + Sync {
+    browser_with_preconnect(query, false)
+}
+
+/// Like [`browser`], optionally first opening a connection to the listener
+/// that never sends a request.
+fn browser_with_preconnect(query: &'static str, preconnect: bool) -> impl Fn(&str) + Send + Sync {
+    move |url: &str| {
+        let url = url.to_string();
+        tokio::spawn(async move {
+            let http = pocketbase_sdk::reqwest::Client::new();
+            assert_eq!(http.get(&url).send().await.unwrap().status(), 200);
+
+            let (_, redirect) = url.split_once("redirect_uri=").unwrap();
+            let redirect = percent_decode_str(redirect).decode_utf8().unwrap();
+            let address = redirect
+                .trim_start_matches("http://")
+                .split('/')
+                .next()
+                .unwrap()
+                .to_string();
+            let _idle = match preconnect {
+                true => Some(TcpStream::connect(address).await.unwrap()),
+                false => None,
+            };
+            let favicon = redirect.replace("/callback", "/favicon.ico");
+            assert_eq!(http.get(&favicon).send().await.unwrap().status(), 404);
+            http.get(format!("{}?{}", redirect, query))
+                .send()
+                .await
+                .unwrap();
+        });
+    }
+}
+
+#[tokio::test]
+async fn loopback_sign_in() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let (client, meta) = client
+        .oauth2_loopback("users", "github", browser("code=code1&state=s1"))
+        .timeout(Duration::from_secs(10))
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(meta.name, "Ada");
+    assert_eq!(client.auth().token(), Some("token1"));
+}
+
+#[tokio::test]
+async fn loopback_ignores_idle_connections() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let (client, _) = client
+        .oauth2_loopback(
+            "users",
+            "github",
+            browser_with_preconnect("code=code1&state=s1", true),
+        )
+        .timeout(Duration::from_secs(5))
+        .call()
+        .await
+        .unwrap();
+
+    assert_eq!(client.auth().token(), Some("token1"));
+}
+
+#[tokio::test]
+async fn loopback_rejects_wrong_state() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let error = client
+        .oauth2_loopback("users", "github", browser("code=code1&state=forged"))
+        .timeout(Duration::from_secs(10))
+        .call()
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::OAuth2(_)));
+}
+
+#[tokio::test]
+async fn loopback_reports_denied_consent() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let error = client
+        .oauth2_loopback("users", "github", browser("error=access_denied&state=s1"))
+        .timeout(Duration::from_secs(10))
+        .call()
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(error.to_string(), "oauth2 error: access_denied");
+}
+
+#[tokio::test]
+async fn loopback_unknown_provider() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let error = client
+        .oauth2_loopback("users", "google", |_: &str| {})
+        .call()
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::OAuth2(_)));
+}
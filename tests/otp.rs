@@ -0,0 +1,106 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::Error;
+use serde_json::json;
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/request-otp")
+            .json_body(json!({ "email": "ada@example.com" }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "otpId": "otp1" }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/auth-with-otp")
+            .json_body(json!({ "otpId": "otp1", "password": "123456" }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "token": "token1",
+                "record": { "id": "u1", "collectionId": "c1", "collectionName": "users" }
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/auth-with-otp")
+            .json_body(json!({ "otpId": "otp1", "password": "000000" }));
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 400,
+                "message": "Invalid or expired OTP",
+                "data": {}
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/users/auth-with-otp")
+            .json_body(json!({ "otpId": "", "password": "123456" }));
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "status": 400,
+                "message": "An error occurred while validating the submitted data.",
+                "data": {
+                    "otpId": { "code": "validation_required", "message": "Cannot be blank." }
+                }
+            }));
+    });
+
+    server
+}
+
+#[tokio::test]
+async fn otp_sign_in() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let otp_id = client
+        .request_otp("users", "ada@example.com")
+        .await
+        .unwrap();
+    assert_eq!(otp_id, "otp1");
+
+    let client = client
+        .auth_with_otp("users", &otp_id, "123456")
+        .await
+        .unwrap();
+    assert_eq!(client.auth().token(), Some("token1"));
+}
+
+#[tokio::test]
+async fn invalid_otp() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let error = client
+        .auth_with_otp("users", "otp1", "000000")
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::InvalidOtp(_)));
+    assert_eq!(error.status(), Some(400));
+}
+
+#[tokio::test]
+async fn otp_validation_error() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let error = client
+        .auth_with_otp("users", "", "123456")
+        .await
+        .err()
+        .unwrap();
+
+    assert!(error.validation_errors().unwrap().contains("otpId"));
+}
@@ -0,0 +1,130 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::error::Error;
+use pocketbase_sdk::query::{Expand, Sort};
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn list_with_sort_and_expand() {
+    let server = MockServer::start();
+    let list_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("sort", "-created,title")
+            .query_param("expand", "author.profile,comments_via_post,tags.category");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "page": 1, "perPage": 100, "totalItems": 0, "items": [] }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    client
+        .collection("posts")
+        .list()
+        .sort(Sort::new().desc("created").asc("title"))
+        .expand(
+            Expand::new()
+                .relation("author.profile")
+                .back_relation("comments", "post")
+                .nested("tags", Expand::new().relation("category")),
+        )
+        .call::<Value>()
+        .await
+        .unwrap();
+
+    list_mock.assert();
+}
+
+#[tokio::test]
+async fn string_options_are_parsed() {
+    let server = MockServer::start();
+    let view_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records/p1")
+            .query_param("expand", "author,comments_via_post");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": "p1" }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    client
+        .collection("posts")
+        .view("p1")
+        .expand("author, comments_via_post")
+        .call::<Value>()
+        .await
+        .unwrap();
+
+    view_mock.assert();
+    assert_eq!(Sort::from("-created,+title").to_string(), "-created,title");
+}
+
+#[tokio::test]
+async fn sort_by_rowid() {
+    let server = MockServer::start();
+    let list_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("sort", "-views,-@rowid");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "page": 1, "perPage": 100, "totalItems": 0, "items": [] }));
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    client
+        .collection("posts")
+        .list()
+        .sort(Sort::new().desc("views").rowid_desc())
+        .call::<Value>()
+        .await
+        .unwrap();
+
+    list_mock.assert();
+    assert_eq!(Sort::new().rowid().to_string(), "@rowid");
+}
+
+#[tokio::test]
+async fn invalid_options_are_rejected_before_sending() {
+    let server = MockServer::start();
+    let list_mock = server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records");
+        then.status(200);
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let posts = client.collection("posts");
+
+    let invalid_sorts = [
+        Sort::from("-created,,title"),
+        Sort::new().asc("title").desc("title"),
+        Sort::random().asc("title"),
+        Sort::new().desc("@random"),
+        Sort::new().rowid().asc("title"),
+        Sort::random().rowid(),
+        Sort::new(),
+    ];
+    for sort in invalid_sorts {
+        let error = posts.list().sort(sort).call::<Value>().await.unwrap_err();
+        assert!(matches!(error, Error::Query(_)), "{:?}", error);
+    }
+
+    let invalid_expands = [
+        Expand::from("author..profile"),
+        Expand::new().relation("a.b.c.d.e.f.g"),
+        Expand::new().relation("author").relation("author"),
+        Expand::new().relation("author profile"),
+    ];
+    for expand in invalid_expands {
+        let error = posts
+            .list()
+            .expand(expand)
+            .call::<Value>()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Query(_)), "{:?}", error);
+    }
+
+    assert_eq!(list_mock.calls(), 0);
+}
@@ -0,0 +1,156 @@
+use chrono::{TimeZone, Utc};
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::records::Record;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct Post {
+    title: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct User {
+    name: String,
+}
+
+fn post(id: &str) -> serde_json::Value {
+    json!({
+        "id": id,
+        "collectionId": "c1",
+        "collectionName": "posts",
+        "created": "2024-01-02 03:04:05.678Z",
+        "updated": "2024-01-03 03:04:05.678Z",
+        "title": "Hello",
+        "author": "u1",
+        "expand": {
+            "author": {
+                "id": "u1",
+                "collectionId": "c2",
+                "collectionName": "users",
+                "created": "2023-12-01 00:00:00.000Z",
+                "updated": "",
+                "name": "Ada"
+            }
+        }
+    })
+}
+
+fn mockserver() -> MockServer {
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/p1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(post("p1"));
+    });
+
+    server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 100,
+                "totalItems": 2,
+                "items": [post("p1"), post("p2")]
+            }));
+    });
+
+    server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/posts/records")
+            .json_body(json!({ "title": "Hello" }));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(post("p3"));
+    });
+
+    server.mock(|when, then| {
+        when.method(PATCH).path("/api/collections/posts/records/p1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(post("p1"));
+    });
+
+    server
+}
+
+#[tokio::test]
+async fn view_record() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+
+    let record = client
+        .collection("posts")
+        .view("p1")
+        .call::<Record<Post>>()
+        .await
+        .unwrap();
+
+    assert_eq!(record.id, "p1");
+    assert_eq!(record.collection_name, "posts");
+    assert_eq!(record.data.title, "Hello");
+    assert_eq!(
+        record.created,
+        Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap() + chrono::Duration::milliseconds(678)
+    );
+
+    let author = record.expanded::<Record<User>>("author").unwrap().unwrap();
+    assert_eq!(author.data.name, "Ada");
+    assert_eq!(author.updated, chrono::DateTime::<Utc>::default());
+    assert!(record.expanded::<Record<User>>("editor").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn list_create_and_update_records() {
+    let server = mockserver();
+    let client = Client::new(server.base_url().as_str());
+    let posts = client.collection("posts");
+
+    let list = posts.list().call::<Record<Post>>().await.unwrap();
+    assert_eq!(list.items[1].id, "p2");
+
+    let created = posts
+        .create(Post {
+            title: "Hello".to_string(),
+        })
+        .call_as::<Record<Post>>()
+        .await
+        .unwrap();
+    assert_eq!(created.id, "p3");
+
+    let updated = posts
+        .update(
+            "p1",
+            Post {
+                title: "Hello".to_string(),
+            },
+        )
+        .call::<Record<Post>>()
+        .await
+        .unwrap();
+    assert_eq!(updated.updated.to_string(), "2024-01-03 03:04:05.678 UTC");
+}
+
+#[test]
+fn serialize_record() {
+    let record: Record<Post> = serde_json::from_value(post("p1")).unwrap();
+    let value = serde_json::to_value(&record).unwrap();
+
+    assert_eq!(value["created"], "2024-01-02 03:04:05.678Z");
+    assert_eq!(value["title"], "Hello");
+}
+
+#[test]
+fn file_url_of_record() {
+    let client = Client::new("http://localhost:8090");
+    let record: Record<Post> = serde_json::from_value(post("p1")).unwrap();
+
+    assert_eq!(
+        client.files().url(&record, "cover.png").build().unwrap(),
+        "http://localhost:8090/api/files/c1/p1/cover.png"
+    );
+}
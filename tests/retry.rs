@@ -0,0 +1,161 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::retry::RetryPolicy;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .max_attempts(3)
+        .backoff(Duration::from_millis(10), Duration::from_millis(50))
+        .jitter(false)
+}
+
+#[tokio::test]
+async fn retries_idempotent_requests() {
+    let server = MockServer::start();
+    let unavailable = server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/1");
+        then.status(503).header("Retry-After", "0");
+    });
+
+    let client = Client::builder(server.base_url().as_str())
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    let error = client
+        .collection("posts")
+        .view("1")
+        .call::<Value>()
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.status(), Some(503));
+    unavailable.assert_calls(3);
+}
+
+#[tokio::test]
+async fn recovers_after_transient_failure() {
+    let server = MockServer::start();
+    let unavailable = server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/1");
+        then.status(502);
+    });
+    let available = server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({ "id": "1" }));
+    });
+
+    let client = Client::builder(server.base_url().as_str())
+        .retry_policy(retry_policy().backoff(Duration::from_millis(300), Duration::from_secs(1)))
+        .build()
+        .unwrap();
+
+    let posts = client.collection("posts");
+    let view = posts.view("1");
+    let (record, _) = tokio::join!(view.call::<Value>(), async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        unavailable.delete_async().await;
+    });
+
+    assert_eq!(record.unwrap()["id"], "1");
+    available.assert_calls(1);
+}
+
+#[tokio::test]
+async fn does_not_retry_create_by_default() {
+    let server = MockServer::start();
+    let unavailable = server.mock(|when, then| {
+        when.method(POST).path("/api/collections/posts/records");
+        then.status(503);
+    });
+
+    let client = Client::builder(server.base_url().as_str())
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    let result = client
+        .collection("posts")
+        .create(json!({ "title": "a" }))
+        .call()
+        .await;
+
+    assert!(result.is_err());
+    unavailable.assert_calls(1);
+}
+
+#[tokio::test]
+async fn retries_create_when_opted_in() {
+    let server = MockServer::start();
+    let unavailable = server.mock(|when, then| {
+        when.method(POST).path("/api/collections/posts/records");
+        then.status(429);
+    });
+
+    let client = Client::builder(server.base_url().as_str())
+        .retry_policy(retry_policy().retry_non_idempotent(true))
+        .build()
+        .unwrap();
+
+    let result = client
+        .collection("posts")
+        .create(json!({ "title": "a" }))
+        .call()
+        .await;
+
+    assert_eq!(result.unwrap_err().status(), Some(429));
+    unavailable.assert_calls(3);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = MockServer::start();
+    let not_found = server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/1");
+        then.status(404);
+    });
+
+    let client = Client::builder(server.base_url().as_str())
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    let error = client
+        .collection("posts")
+        .view("1")
+        .call::<Value>()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_not_found());
+    not_found.assert_calls(1);
+}
+
+#[tokio::test]
+async fn gives_up_when_retry_after_exceeds_max_backoff() {
+    let server = MockServer::start();
+    let throttled = server.mock(|when, then| {
+        when.method(GET).path("/api/collections/posts/records/1");
+        then.status(429).header("Retry-After", "3600");
+    });
+
+    let client = Client::builder(server.base_url().as_str())
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    let error = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.collection("posts").view("1").call::<Value>(),
+    )
+    .await
+    .unwrap()
+    .unwrap_err();
+
+    assert_eq!(error.status(), Some(429));
+    throttled.assert_calls(1);
+}
@@ -0,0 +1,139 @@
+use futures_util::StreamExt;
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+#[derive(Debug, Deserialize)]
+struct Post {
+    id: String,
+}
+
+fn mock_pages(server: &MockServer) -> Vec<httpmock::Mock<'_>> {
+    let pages = [vec!["1", "2"], vec!["3", "4"], vec!["5"]];
+
+    pages
+        .iter()
+        .enumerate()
+        .map(|(index, ids)| {
+            let items: Vec<_> = ids.iter().map(|id| json!({ "id": id })).collect();
+            server.mock(|when, then| {
+                when.method(GET)
+                    .path("/api/collections/posts/records")
+                    .query_param("perPage", "2")
+                    .query_param("skipTotal", "1")
+                    .query_param("page", (index + 1).to_string());
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .json_body(json!({
+                        "page": index + 1,
+                        "perPage": 2,
+                        "totalItems": -1,
+                        "items": items
+                    }));
+            })
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn stream_records() {
+    let server = MockServer::start();
+    mock_pages(&server);
+    let client = Client::new(server.base_url().as_str());
+
+    let ids: Vec<String> = client
+        .collection("posts")
+        .list()
+        .per_page(2)
+        .skip_total(true)
+        .into_stream::<Post>()
+        .map(|post| post.unwrap().id)
+        .collect()
+        .await;
+
+    assert_eq!(ids, vec!["1", "2", "3", "4", "5"]);
+}
+
+#[tokio::test]
+async fn next_page_is_prefetched() {
+    let server = MockServer::start();
+    let pages = mock_pages(&server);
+    let client = Client::new(server.base_url().as_str());
+
+    let mut stream = Box::pin(
+        client
+            .collection("posts")
+            .list()
+            .per_page(2)
+            .skip_total(true)
+            .into_page_stream::<Post>(),
+    );
+    let first = stream.next().await.unwrap().unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(pages[1].calls(), 1);
+    assert_eq!(pages[2].calls(), 0);
+}
+
+#[tokio::test]
+async fn stream_ends_after_error() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("page", "1");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "page": 1,
+                "perPage": 1,
+                "totalItems": 3,
+                "items": [{ "id": "1" }]
+            }));
+    });
+    server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/collections/posts/records")
+            .query_param("page", "2");
+        then.status(403)
+            .header("content-type", "application/json")
+            .json_body(json!({ "status": 403, "message": "Forbidden.", "data": {} }));
+    });
+    let client = Client::new(server.base_url().as_str());
+
+    let results: Vec<_> = client
+        .collection("posts")
+        .list()
+        .per_page(1)
+        .into_stream::<Post>()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().id, "1");
+    assert!(results[1].as_ref().unwrap_err().is_forbidden());
+}
+
+#[test]
+fn stream_is_lazy() {
+    let server = MockServer::start();
+    let pages = mock_pages(&server);
+    let client = Client::new(server.base_url().as_str());
+
+    let stream = client
+        .collection("posts")
+        .list()
+        .per_page(2)
+        .skip_total(true)
+        .into_stream::<Post>();
+    let ids: Vec<String> = tokio::runtime::Runtime::new().unwrap().block_on(async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(pages[0].calls(), 0);
+        stream.map(|post| post.unwrap().id).collect().await
+    });
+
+    assert_eq!(ids, vec!["1", "2", "3", "4", "5"]);
+}
@@ -0,0 +1,130 @@
+use httpmock::prelude::*;
+use pocketbase_sdk::client::Client;
+use pocketbase_sdk::files::FileUpload;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, Serialize, Default, Deserialize)]
+pub struct Document {
+    pub title: String,
+}
+
+fn record_response() -> serde_json::Value {
+    json!({
+        "collectionId": "a98f514eb05f454",
+        "collectionName": "documents",
+        "id": "d1",
+        "updated": "2022-06-25 11:03:50.052Z",
+        "created": "2022-06-25 11:03:35.163Z",
+        "title": "report"
+    })
+}
+
+#[tokio::test]
+async fn create_with_files() {
+    let server = MockServer::start();
+    let file_name = format!(
+        "pocketbase-sdk-create-with-files-{}.txt",
+        std::process::id()
+    );
+    let path = std::env::temp_dir().join(&file_name);
+    std::fs::write(&path, "from disk").unwrap();
+    let create_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/api/collections/documents/records")
+            .header_includes("content-type", "multipart/form-data")
+            .body_includes("name=\"@jsonPayload\"")
+            .body_includes("{\"title\":\"report\"}")
+            .body_includes("name=\"attachment\"; filename=\"report.pdf\"")
+            .body_includes("Content-Type: application/pdf")
+            .body_includes("%PDF-1.4")
+            .body_includes(format!("filename=\"{}\"", file_name))
+            .body_includes("from disk")
+            .body_includes("name=\"notes\"; filename=\"notes.md\"")
+            .body_includes("# streamed");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(record_response());
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let response = client
+        .collection("documents")
+        .create(Document {
+            title: "report".to_string(),
+        })
+        .file(
+            "attachment",
+            FileUpload::bytes(b"%PDF-1.4".to_vec(), "report.pdf").content_type("application/pdf"),
+        )
+        .file("attachment", FileUpload::path(&path))
+        .file("notes", FileUpload::reader(&b"# streamed"[..], "notes.md"))
+        .call()
+        .await;
+
+    std::fs::remove_file(&path).unwrap();
+    create_mock.assert();
+    assert_eq!(response.unwrap().id, "d1");
+}
+
+#[tokio::test]
+async fn update_with_file_modifiers() {
+    let server = MockServer::start();
+    let update_mock = server.mock(|when, then| {
+        when.method(PATCH)
+            .path("/api/collections/documents/records/d1")
+            .header_includes("content-type", "multipart/form-data")
+            .body_includes("{\"documents-\":[\"old_1.pdf\",\"old_2.pdf\"],\"title\":\"report\"}")
+            .body_includes("name=\"documents+\"; filename=\"new.pdf\"");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(record_response());
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    let document = client
+        .collection("documents")
+        .update(
+            "d1",
+            Document {
+                title: "report".to_string(),
+            },
+        )
+        .remove_file("documents", "old_1.pdf")
+        .remove_file("documents", "old_2.pdf")
+        .append_file("documents", FileUpload::bytes(b"new".to_vec(), "new.pdf"))
+        .call::<Document>()
+        .await
+        .unwrap();
+
+    update_mock.assert();
+    assert_eq!(document.title, "report");
+}
+
+#[tokio::test]
+async fn remove_files_without_upload_sends_json() {
+    let server = MockServer::start();
+    let update_mock = server.mock(|when, then| {
+        when.method(PATCH)
+            .path("/api/collections/documents/records/d1")
+            .header("content-type", "application/json")
+            .json_body(json!({ "title": "report", "documents-": ["old.pdf"] }));
+        then.status(204);
+    });
+
+    let client = Client::new(server.base_url().as_str());
+    client
+        .collection("documents")
+        .update(
+            "d1",
+            Document {
+                title: "report".to_string(),
+            },
+        )
+        .remove_file("documents", "old.pdf")
+        .send()
+        .await
+        .unwrap();
+
+    update_mock.assert();
+}